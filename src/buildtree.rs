@@ -0,0 +1,123 @@
+// What items build from, all the way down to the basic items
+
+use crate::model::Item;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildTree
+{
+    pub Key: String,
+    // None when the component is not in the item list, e.g. it was filtered out
+    pub Item: Option<Item>,
+    pub Components: Vec<BuildTree>,
+}
+
+fn FindItem<'a>(Key: &str, InItems: &'a [Item]) -> Option<&'a Item>
+{
+    InItems.iter().find(|item| item.Key == Key)
+}
+
+fn GetBuildTreeOnPath(Key: &str, InItems: &[Item], Path: &mut Vec<String>) -> BuildTree
+{
+    let item = FindItem(Key, InItems);
+    let mut components: Vec<BuildTree> = Vec::new();
+
+    // Guards against an item listed as its own component somewhere down the tree
+    if !Path.iter().any(|key| key == Key)
+    {
+        Path.push(Key.to_string());
+        for component in item.map_or(&[][..], |item| &item.Components)
+        {
+            components.push(GetBuildTreeOnPath(component, InItems, Path));
+        }
+        Path.pop();
+    }
+
+    BuildTree { Key: Key.to_string(), Item: item.cloned(), Components: components }
+}
+
+// The full tree of the item with Key, e.g. Daedalus -> Crystalys -> Broadsword, Blades of Attack
+pub fn GetBuildTree(Key: &str, InItems: &[Item]) -> BuildTree
+{
+    GetBuildTreeOnPath(Key, InItems, &mut Vec::new())
+}
+
+// The items that get a tree: the ones with components, once per Key. Every level of a multi level item has the same
+// components, and consumed copies like Moon Shard (Consumed) are the same item.
+pub fn GetBuildTreeItems(InItems: &[Item]) -> Vec<&Item>
+{
+    let mut treeItems: Vec<&Item> = Vec::new();
+    for item in InItems.iter().filter(|item| !item.Components.is_empty() && !item.IsConsumed)
+    {
+        if !treeItems.iter().any(|treeItem| treeItem.Key == item.Key)
+        {
+            treeItems.push(item);
+        }
+    }
+    treeItems
+}
+
+// The tree top down with the depth of each node, the item itself at depth 0
+pub fn FlattenBuildTree(Tree: &BuildTree) -> Vec<(usize, &BuildTree)>
+{
+    let mut nodes = vec![(0, Tree)];
+    for component in &Tree.Components
+    {
+        nodes.extend(FlattenBuildTree(component).into_iter().map(|(depth, node)| (depth + 1, node)));
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn TestItem(Key: &str, Cost: i32, Components: &[&str]) -> Item
+    {
+        Item{Key: Key.to_string(), Cost, Components: Components.iter().map(|c| c.to_string()).collect(), ..Default::default()}
+    }
+
+    #[test]
+    fn DaedalusTree()
+    {
+        let items = vec![
+            TestItem("item_greater_crit", 5100, &["item_lesser_crit", "item_demon_edge"]),
+            TestItem("item_lesser_crit", 2000, &["item_broadsword", "item_blades_of_attack"]),
+            TestItem("item_demon_edge", 2200, &[]),
+            TestItem("item_broadsword", 1000, &[]),
+        ];
+
+        let tree = GetBuildTree("item_greater_crit", &items);
+        let nodes: Vec<(usize, &str, i32)> = FlattenBuildTree(&tree).into_iter()
+            .map(|(depth, node)| (depth, node.Key.as_str(), node.Item.as_ref().map_or(0, |item| item.Cost)))
+            .collect();
+
+        // Blades of Attack is not in the list but still shows up
+        assert_eq!(nodes, [
+            (0, "item_greater_crit", 5100),
+            (1, "item_lesser_crit", 2000),
+            (2, "item_broadsword", 1000),
+            (2, "item_blades_of_attack", 0),
+            (1, "item_demon_edge", 2200),
+        ]);
+    }
+
+    #[test]
+    fn OneTreePerItem()
+    {
+        let moonShard = TestItem("item_moon_shard", 4000, &["item_hyperstone", "item_hyperstone"]);
+        let consumed = Item{IsConsumed: true, ..moonShard.clone()};
+        let levels: Vec<Item> = (1..=3).map(|level| Item{Level: level, MaxLevel: 3, ..TestItem("item_test", 0, &["item_broadsword"])}).collect();
+
+        let items: Vec<Item> = [moonShard, consumed, TestItem("item_hyperstone", 2000, &[])].into_iter().chain(levels).collect();
+        let treeItems: Vec<(&str, i32)> = GetBuildTreeItems(&items).into_iter().map(|item| (item.Key.as_str(), item.Level)).collect();
+        assert_eq!(treeItems, [("item_moon_shard", 1), ("item_test", 1)]);
+    }
+
+    #[test]
+    fn StopsAtCycles()
+    {
+        let items = vec![TestItem("item_a", 0, &["item_b"]), TestItem("item_b", 0, &["item_a"])];
+        assert_eq!(FlattenBuildTree(&GetBuildTree("item_a", &items)).len(), 3);
+    }
+}
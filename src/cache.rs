@@ -0,0 +1,170 @@
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, IoError, Result};
+
+// How a cached response was obtained
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheStatus
+{
+    // Downloaded, the cache did not have it or it had changed
+    Fresh,
+    // Server answered 304, the cached copy is still current
+    Revalidated,
+    // Network failed, using the cached copy as is. Has the request error.
+    Stale(String),
+}
+
+impl fmt::Display for CacheStatus
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            CacheStatus::Fresh => write!(f, "fresh"),
+            CacheStatus::Revalidated => write!(f, "revalidated"),
+            CacheStatus::Stale(e) => write!(f, "stale, the request failed ({}) so the cached copy is used", e),
+        }
+    }
+}
+
+// Stored next to each cached body
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheMeta
+{
+    Url: String,
+    ETag: Option<String>,
+    LastModified: Option<String>,
+}
+
+fn BodyPath(CacheDir: &Path, Key: &str) -> PathBuf
+{
+    CacheDir.join(Key)
+}
+
+fn MetaPath(CacheDir: &Path, Key: &str) -> PathBuf
+{
+    CacheDir.join(format!("{}.meta.json", Key))
+}
+
+fn ReadCached(CacheDir: &Path, Key: &str, Url: &str) -> Option<(String, CacheMeta)>
+{
+    let body = std::fs::read_to_string(BodyPath(CacheDir, Key)).ok()?;
+    let metaJson = std::fs::read_to_string(MetaPath(CacheDir, Key)).ok()?;
+    let meta: CacheMeta = serde_json::from_str(&metaJson).ok()?;
+
+    // The url changed, the cached copy belongs to something else
+    if meta.Url != Url
+    {
+        return None;
+    }
+
+    Some((body, meta))
+}
+
+fn WriteCached(CacheDir: &Path, Key: &str, Body: &str, Meta: &CacheMeta) -> Result<()>
+{
+    let bodyPath = BodyPath(CacheDir, Key);
+    if let Some(parent) = bodyPath.parent()
+    {
+        std::fs::create_dir_all(parent).map_err(IoError(parent))?;
+    }
+
+    let metaPath = MetaPath(CacheDir, Key);
+    std::fs::write(&bodyPath, Body).map_err(IoError(&bodyPath))?;
+    std::fs::write(&metaPath, serde_json::to_string_pretty(Meta).unwrap()).map_err(IoError(&metaPath))
+}
+
+fn HeaderString(Response: &reqwest::blocking::Response, Name: reqwest::header::HeaderName) -> Option<String>
+{
+    Response.headers().get(Name).and_then(|v| v.to_str().ok()).map(|v| v.to_string())
+}
+
+// Gets Url through the on-disk cache in CacheDir, Key is the file name used inside the cache.
+// Revalidates with If-None-Match/If-Modified-Since and falls back to the cached copy when the request fails.
+pub fn GetCached(CacheDir: &Path, Url: &str, Key: &str) -> Result<(String, CacheStatus)>
+{
+    let cached = ReadCached(CacheDir, Key, Url);
+
+    let client = reqwest::blocking::Client::new();
+    let mut request = client.get(Url);
+    if let Some((_, meta)) = &cached
+    {
+        if let Some(etag) = &meta.ETag
+        {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(lastModified) = &meta.LastModified
+        {
+            request = request.header(IF_MODIFIED_SINCE, lastModified);
+        }
+    }
+
+    let response = request.send().and_then(|r| r.error_for_status()).and_then(|r|
+    {
+        let status = r.status();
+        let meta = CacheMeta
+        {
+            Url: Url.to_string(),
+            ETag: HeaderString(&r, ETAG),
+            LastModified: HeaderString(&r, LAST_MODIFIED),
+        };
+        Ok((status, meta, r.text()?))
+    });
+
+    match (response, cached)
+    {
+        (Ok((status, _, _)), Some((body, _))) if status == StatusCode::NOT_MODIFIED =>
+        {
+            Ok((body, CacheStatus::Revalidated))
+        }
+        (Ok((_, meta, body)), _) =>
+        {
+            WriteCached(CacheDir, Key, &body, &meta)?;
+            Ok((body, CacheStatus::Fresh))
+        }
+        (Err(e), Some((body, _))) => Ok((body, CacheStatus::Stale(e.to_string()))),
+        (Err(e), None) => Err(Error::Network { Url: Url.to_string(), Source: e }),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // An empty directory of its own for each test
+    fn TestCacheDir(Name: &str) -> PathBuf
+    {
+        let dir = std::env::temp_dir().join(format!("Dota2Items-cache-{}-{}", Name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn RoundTrip()
+    {
+        let dir = TestCacheDir("roundtrip");
+        let meta = CacheMeta { Url: "https://example.com/items.json".to_string(), ETag: Some("\"abc\"".to_string()), LastModified: None };
+        WriteCached(&dir, "d2vpkr/items.json", "{}", &meta).unwrap();
+
+        let (body, readMeta) = ReadCached(&dir, "d2vpkr/items.json", "https://example.com/items.json").unwrap();
+        assert_eq!((body.as_str(), readMeta.ETag.as_deref()), ("{}", Some("\"abc\"")));
+        assert!(ReadCached(&dir, "dotaconstants/items.json", "https://example.com/items.json").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ChangedUrlInvalidates()
+    {
+        let dir = TestCacheDir("url");
+        let meta = CacheMeta { Url: "https://example.com/old.json".to_string(), ..Default::default() };
+        WriteCached(&dir, "items.json", "{}", &meta).unwrap();
+
+        assert!(ReadCached(&dir, "items.json", "https://example.com/new.json").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
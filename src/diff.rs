@@ -0,0 +1,80 @@
+// Compares two item lists, e.g. before and after a patch
+
+use std::collections::BTreeMap;
+
+use crate::export::ItemColumns;
+use crate::model::{GetItemDisplayName, Item};
+
+#[derive(Debug, PartialEq)]
+pub enum ItemChange
+{
+    Added(String),
+    Removed(String),
+    // Item name and the changed columns as (column, old, new)
+    Changed(String, Vec<(&'static str, String, String)>),
+}
+
+// Items are matched by their display name, so every level of a multi level item is compared on its own
+pub fn DiffItems(OldItems: &[Item], NewItems: &[Item]) -> Vec<ItemChange>
+{
+    let oldByName: BTreeMap<String, &Item> = OldItems.iter().map(|item| (GetItemDisplayName(item), item)).collect();
+    let newByName: BTreeMap<String, &Item> = NewItems.iter().map(|item| (GetItemDisplayName(item), item)).collect();
+
+    let mut changes: Vec<ItemChange> = Vec::new();
+
+    for (name, oldItem) in &oldByName
+    {
+        match newByName.get(name)
+        {
+            None => changes.push(ItemChange::Removed(name.clone())),
+            Some(newItem) =>
+            {
+                let changed: Vec<(&'static str, String, String)> = ItemColumns(oldItem).into_iter()
+                    .zip(ItemColumns(newItem))
+                    .filter(|((_, oldValue), (_, newValue))| oldValue != newValue)
+                    .map(|((column, oldValue), (_, newValue))| (column, oldValue, newValue))
+                    .collect();
+
+                if !changed.is_empty()
+                {
+                    changes.push(ItemChange::Changed(name.clone(), changed));
+                }
+            }
+        }
+    }
+
+    for name in newByName.keys()
+    {
+        if !oldByName.contains_key(name)
+        {
+            changes.push(ItemChange::Added(name.clone()));
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn TestItem(Name: &str, Cost: i32, Level: i32, MaxLevel: i32) -> Item
+    {
+        Item{Name: Name.to_string(), Cost, Level, MaxLevel, ..Default::default()}
+    }
+
+    #[test]
+    fn AddedRemovedAndChanged()
+    {
+        let oldItems = vec![TestItem("Blink Dagger", 2250, 1, 1), TestItem("Dagon", 2700, 1, 2), TestItem("Dagon", 3950, 2, 2), TestItem("Tango", 90, 1, 1)];
+        let newItems = vec![TestItem("Blink Dagger", 2250, 1, 1), TestItem("Dagon", 2850, 1, 2), TestItem("Dagon", 3950, 2, 2), TestItem("Clarity", 50, 1, 1)];
+
+        // Each level of Dagon is compared on its own, only level 1 changed
+        assert_eq!(DiffItems(&oldItems, &newItems), [
+            ItemChange::Changed("Dagon (Level 1)".to_string(), vec![("Cost", "2700".to_string(), "2850".to_string())]),
+            ItemChange::Removed("Tango".to_string()),
+            ItemChange::Added("Clarity".to_string()),
+        ]);
+    }
+}
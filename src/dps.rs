@@ -0,0 +1,198 @@
+// Damage per second of a hero with a set of items, the same math as Dota2BuyDps.xlsm
+
+use crate::herostats::{GetHeroStatsAtLevel, ATTACK_SPEED_PER_AGI};
+use crate::model::{Hero, Item, PrimaryAttribute};
+use crate::stacking::CombineItems;
+
+pub const MIN_ATTACK_SPEED: f32 = 20.0;
+pub const MAX_ATTACK_SPEED: f32 = 700.0;
+// Universal heroes get this much damage from each attribute
+pub const UNIVERSAL_DAMAGE_PER_ATTRIBUTE: f32 = 0.7;
+
+// The items of a build added together
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ItemTotals
+{
+    pub Damage: f32,
+    pub AttackSpeed: f32,
+    pub Str: f32,
+    pub Agi: f32,
+    pub Int: f32,
+    pub CritChance: f32,
+    pub CritMultiplier: f32,
+    // Average magic damage added to each attack by chain lightning/bash procs
+    pub ProcDamage: f32,
+    // Fraction of each attack dealt to the other enemies nearby
+    pub CleavePercent: f32,
+    // Average chain lightning damage per attack on each enemy it hits, and how many it hits
+    pub ChainDamage: f32,
+    pub ChainStrikes: i32,
+}
+
+pub fn IsRanged(InHero: &Hero) -> bool
+{
+    InHero.AttackType == "Ranged"
+}
+
+// Attacks per second for a total attack speed
+pub fn AttacksPerSecond(BAT: f32, AttackSpeed: f32) -> f32
+{
+    AttackSpeed.clamp(MIN_ATTACK_SPEED, MAX_ATTACK_SPEED) / (100.0 * BAT)
+}
+
+// Damage of the average hit when CritChance of the hits deal CritMultiplier times the damage
+pub fn AverageHitDamage(Damage: f32, CritChance: f32, CritMultiplier: f32) -> f32
+{
+    Damage * (1.0 + CritChance * (CritMultiplier - 1.0))
+}
+
+// Damage from the hero's primary attribute
+pub fn PrimaryAttributeDamage(InHero: &Hero, Str: f32, Agi: f32, Int: f32) -> f32
+{
+    match InHero.PrimaryAttribute
+    {
+        PrimaryAttribute::Strength => Str,
+        PrimaryAttribute::Agility => Agi,
+        PrimaryAttribute::Intelligence => Int,
+        PrimaryAttribute::Universal => UNIVERSAL_DAMAGE_PER_ATTRIBUTE * (Str + Agi + Int),
+    }
+}
+
+// Average damage of InHero at level 1 without items, attribute damage included
+pub fn HeroBaseDamage(InHero: &Hero) -> f32
+{
+    GetHeroStatsAtLevel(InHero, 1).Damage
+}
+
+// Adds up the items for InHero, following the stacking rules of CombineItems
+pub fn SumItems(InHero: &Hero, InItems: &[Item]) -> ItemTotals
+{
+    let ranged = IsRanged(InHero);
+    let build = CombineItems(InItems);
+    let onHit = &build.OnHit;
+
+    let bashChance = if ranged { onHit.BashChance_Ranged } else { onHit.BashChance_Melee };
+    let chainDamage = onHit.ChainChance * onHit.ChainDamage;
+
+    ItemTotals
+    {
+        Damage: (build.Damage + if ranged { build.Damage_Ranged } else { build.Damage_Melee }) as f32,
+        AttackSpeed: build.AttackSpeed as f32,
+        Str: build.Str as f32,
+        Agi: build.Agi as f32,
+        Int: build.Int as f32,
+        CritChance: build.CritChance,
+        CritMultiplier: build.CritMultiplier,
+        ProcDamage: chainDamage + bashChance * onHit.BashDamage + onHit.TrueStrikeChance * onHit.PierceDamage,
+        // Cleave only works for melee heroes
+        CleavePercent: if ranged { 0.0 } else { onHit.CleavePercent },
+        ChainDamage: chainDamage,
+        ChainStrikes: onHit.ChainStrikes,
+    }
+}
+
+// Damage per second of InHero with BaseDamage (without items) and InItems
+pub fn Dps(InHero: &Hero, BaseDamage: f32, InItems: &[Item]) -> f32
+{
+    DpsAgainst(InHero, BaseDamage, InItems, 1)
+}
+
+// Damage per second summed over Targets enemies standing together, so cleave and chain lightning count.
+// BaseDamage only replaces the damage, the attack speed is always that of InHero at level 1.
+pub fn DpsAgainst(InHero: &Hero, BaseDamage: f32, InItems: &[Item], Targets: i32) -> f32
+{
+    let totals = SumItems(InHero, InItems);
+
+    let attackSpeed = GetHeroStatsAtLevel(InHero, 1).AttackSpeed + totals.Agi * ATTACK_SPEED_PER_AGI + totals.AttackSpeed;
+    let damage = BaseDamage + totals.Damage + PrimaryAttributeDamage(InHero, totals.Str, totals.Agi, totals.Int);
+
+    let attackDamage = AverageHitDamage(damage, totals.CritChance, totals.CritMultiplier);
+    let otherTargets = (Targets - 1).max(0);
+    let cleaveDamage = otherTargets as f32 * totals.CleavePercent * attackDamage;
+    let chainDamage = otherTargets.min(totals.ChainStrikes - 1).max(0) as f32 * totals.ChainDamage;
+
+    let hitDamage = attackDamage + totals.ProcDamage + cleaveDamage + chainDamage;
+    hitDamage * AttacksPerSecond(InHero.BAT, attackSpeed)
+}
+
+// Damage per second against Targets enemies the bought items add per 1000 gold. Neutral items are found rather
+// than bought, so they are left out of both the damage and the gold. None when nothing in the build costs gold.
+pub fn DpsPerThousandGold(InHero: &Hero, BaseDamage: f32, InItems: &[Item], Targets: i32) -> Option<f32>
+{
+    let boughtItems: Vec<Item> = InItems.iter().filter(|item| !item.IsNeutralItem).cloned().collect();
+    let gold: i32 = boughtItems.iter().map(|item| item.Cost).sum();
+    if gold <= 0
+    {
+        return None;
+    }
+
+    let addedDps = DpsAgainst(InHero, BaseDamage, &boughtItems, Targets) - DpsAgainst(InHero, BaseDamage, &[], Targets);
+    Some(addedDps * 1000.0 / gold as f32)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::model::OnHitEffects;
+    use crate::testutil::{Close, TestHero};
+
+    // Without agility the hero attacks at 100 attack speed, 1/1.7 attacks per second
+    fn NoAgiHero() -> Hero
+    {
+        Hero{BaseAgi: 0.0, ..TestHero()}
+    }
+
+    #[test]
+    fn DpsWithoutItems()
+    {
+        assert!(Close(Dps(&NoAgiHero(), 51.0, &[]), 30.0));
+    }
+
+    #[test]
+    fn BaseAgilityGivesAttackSpeed()
+    {
+        // 20 agility is 120 attack speed, also when the base damage is given
+        assert!(Close(Dps(&TestHero(), 51.0, &[]), 51.0 * 1.2 / 1.7));
+
+        let yasha = Item{Agi: 16, AttackSpeed: 10, ..Default::default()};
+        assert!(Close(Dps(&TestHero(), 51.0, &[yasha]), (51.0 + 16.0) * 1.46 / 1.7));
+    }
+
+    #[test]
+    fn OnlyStrongestCritCounts()
+    {
+        let crystalys = Item{CritChance: 0.3, CritMultiplier: 1.6, ..Default::default()};
+        let daedalus = Item{CritChance: 0.3, CritMultiplier: 2.25, ..Default::default()};
+
+        let totals = SumItems(&TestHero(), &[crystalys, daedalus]);
+        assert_eq!((totals.CritChance, totals.CritMultiplier), (0.3, 2.25));
+    }
+
+    #[test]
+    fn NeutralItemsAreNotBought()
+    {
+        // 17 damage at 1/1.7 attacks per second is 10 dps for 1000 gold
+        let broadsword = Item{Damage: 17, Cost: 1000, ..Default::default()};
+        let broomHandle = Item{Damage: 18, IsNeutralItem: true, ..Default::default()};
+
+        let perGold = DpsPerThousandGold(&NoAgiHero(), 51.0, &[broadsword, broomHandle.clone()], 1).unwrap();
+        assert!(Close(perGold, 10.0));
+        assert_eq!(DpsPerThousandGold(&NoAgiHero(), 51.0, &[broomHandle], 1), None);
+    }
+
+    #[test]
+    fn CleaveAndChainLightningHitOtherTargets()
+    {
+        let battleFury = Item{OnHit: OnHitEffects{CleavePercent: 0.5, ..Default::default()}, ..Default::default()};
+        let maelstrom = Item{OnHit: OnHitEffects{ChainChance: 0.5, ChainDamage: 100.0, ChainStrikes: 2, ..Default::default()}, ..Default::default()};
+        let build = [battleFury, maelstrom];
+
+        // 51 damage at 1/1.7 attacks per second is 30 dps, both other targets take half of it from cleave.
+        // The chain lightning adds 50 per attack to the attacked target and the second one only.
+        let single = DpsAgainst(&NoAgiHero(), 51.0, &build, 1);
+        let three = DpsAgainst(&NoAgiHero(), 51.0, &build, 3);
+        assert!(Close(single, 30.0 + 50.0 / 1.7));
+        assert!(Close(three, single + 2.0 * 15.0 + 50.0 / 1.7));
+    }
+}
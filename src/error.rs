@@ -0,0 +1,97 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::kv::KvError;
+
+#[derive(Debug)]
+pub enum Error
+{
+    // Downloading a data file failed
+    Network { Url: String, Source: reqwest::Error },
+    // Reading or writing a local file failed
+    Io { Path: PathBuf, Source: std::io::Error },
+    // A file is not valid json/KeyValues
+    Parse { File: String, Message: String },
+    // The data parsed but a value is missing or has the wrong type. Key is the item/hero, Path the json path inside it.
+    Schema { Key: String, Path: String, Message: String },
+    // Writing the spreadsheet failed
+    Write { Path: String, Source: std::io::Error },
+    // A program could not be started
+    Launch { Program: String, Source: std::io::Error },
+    // Bad command line
+    Usage(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Error::Network { Url, Source } => write!(f, "failed to download {}: {}", Url, Source),
+            Error::Io { Path, Source } => write!(f, "{}: {}", Path.display(), Source),
+            Error::Parse { File, Message } => write!(f, "failed to parse {}: {}", File, Message),
+            Error::Schema { Key, Path, Message } if Path.is_empty() || Path == "." => write!(f, "{}: {}", Key, Message),
+            Error::Schema { Key, Path, Message } => write!(f, "{} at {}: {}", Key, Path, Message),
+            Error::Write { Path, Source } => write!(f, "failed to write {}: {}", Path, Source),
+            Error::Launch { Program, Source } => write!(f, "failed to run {}: {}", Program, Source),
+            Error::Usage(Message) => write!(f, "{}", Message),
+        }
+    }
+}
+
+impl std::error::Error for Error
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            Error::Network { Source, .. } => Some(Source),
+            Error::Io { Source, .. } | Error::Write { Source, .. } | Error::Launch { Source, .. } => Some(Source),
+            _ => None,
+        }
+    }
+}
+
+impl From<KvError> for Error
+{
+    fn from(e: KvError) -> Error
+    {
+        Error::Parse { File: e.File.clone(), Message: format!("line {}: {}", e.Line, e.Message) }
+    }
+}
+
+pub fn IoError(FilePath: &Path) -> impl FnOnce(std::io::Error) -> Error + '_
+{
+    move |e| Error::Io { Path: FilePath.to_path_buf(), Source: e }
+}
+
+// Parses json text from File, wrong shaped data is reported with its json path
+pub fn ParseJson<T: DeserializeOwned>(File: &str, JsonData: &str) -> Result<T>
+{
+    let deserializer = &mut serde_json::Deserializer::from_str(JsonData);
+    serde_path_to_error::deserialize(deserializer).map_err(|e|
+    {
+        let path = e.path().to_string();
+        let inner = e.into_inner();
+        if inner.is_data()
+        {
+            Error::Schema { Key: File.to_string(), Path: path, Message: inner.to_string() }
+        }
+        else
+        {
+            Error::Parse { File: File.to_string(), Message: inner.to_string() }
+        }
+    })
+}
+
+// Converts the json of one item/hero (Key) to its typed model
+pub fn FromValue<T: DeserializeOwned>(Key: &str, InValue: Value) -> Result<T>
+{
+    serde_path_to_error::deserialize(InValue).map_err(|e|
+        Error::Schema { Key: Key.to_string(), Path: e.path().to_string(), Message: e.into_inner().to_string() })
+}
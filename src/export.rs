@@ -0,0 +1,300 @@
+extern crate simple_excel_writer as excel;
+use excel::*;
+
+use crate::buildtree::{FlattenBuildTree, GetBuildTree, GetBuildTreeItems};
+use crate::error::{Error, Result};
+use crate::herostats::{GetHeroStatsAtLevel, SHEET_LEVELS};
+use crate::model::{GetAbilityDescription, GetItemDisplayName, Hero, Item};
+use crate::timings::{GetAttackTimings, GetHitTime, SHEET_HITS};
+
+fn SheetError(Sheet: &str) -> impl FnOnce(std::io::Error) -> Error + '_
+{
+    move |e| Error::Write { Path: format!("{} sheet", Sheet), Source: e }
+}
+
+// Writes the workbook to FilePath. The workbook is built in memory so a locked file is reported instead of panicking.
+pub fn CloseWorkbook(wb: &mut Workbook, FilePath: &str) -> Result<()>
+{
+    let writeError = |e| Error::Write { Path: FilePath.to_string(), Source: e };
+    let data = wb.close().map_err(writeError)?.unwrap_or_default();
+    std::fs::write(FilePath, data).map_err(writeError)
+}
+
+pub fn WriteItemsToXlsx(wb: &mut Workbook, InItems: &[Item]) -> Result<()>
+{
+    // Create Sheet
+    let mut sheet = wb.create_sheet("Items");
+
+    // Write to Sheet
+    wb.write_sheet(&mut sheet, |sheet_writer| {
+        let sw = sheet_writer;
+        
+        for item in InItems
+        {
+            WriteItem(sw, item)?;
+        }
+        
+        Ok(())
+    }).map_err(SheetError("Items"))
+}
+
+// The Items sheet columns, in order. Dota2BuyDps.xlsm reads them by position so new ones go at the end.
+pub fn ItemColumns(InItem: &Item) -> Vec<(&'static str, String)>
+{
+    vec![
+        ("Name", GetItemDisplayName(InItem)),
+        ("Cost", InItem.Cost.to_string()),
+        ("Damage", InItem.Damage.to_string()),
+        ("Damage_Melee", InItem.Damage_Melee.to_string()),
+        ("Damage_Ranged", InItem.Damage_Ranged.to_string()),
+        ("AttackSpeed", InItem.AttackSpeed.to_string()),
+        ("Str", InItem.Str.to_string()),
+        ("Agi", InItem.Agi.to_string()),
+        ("Int", InItem.Int.to_string()),
+        ("ArmorCorruption", InItem.ArmorCorruption.to_string()),
+        ("MagicDamage", InItem.MagicDamage.to_string()),
+        ("MagicChance_Melee", InItem.MagicChance_Melee.to_string()),
+        ("MagicChance_Ranged", InItem.MagicChance_Ranged.to_string()),
+        ("CritMultiplier", InItem.CritMultiplier.to_string()),
+        ("CritChance", InItem.CritChance.to_string()),
+        ("IsNeutralItem", InItem.IsNeutralItem.to_string()),
+        ("Armor", InItem.Armor.to_string()),
+        ("Evasion", InItem.Evasion.to_string()),
+        ("MagicResistance", InItem.MagicResistance.to_string()),
+        ("StatusResistance", InItem.StatusResistance.to_string()),
+        ("SlowResistance", InItem.SlowResistance.to_string()),
+        ("Health", InItem.Health.to_string()),
+        ("Mana", InItem.Mana.to_string()),
+        ("HealthRegen", InItem.HealthRegen.to_string()),
+        ("ManaRegen", InItem.ManaRegen.to_string()),
+        ("Lifesteal", InItem.Lifesteal.to_string()),
+        ("SpellLifesteal", InItem.SpellLifesteal.to_string()),
+        ("MoveSpeed", InItem.MoveSpeed.to_string()),
+        ("MoveSpeedPercent", InItem.MoveSpeedPercent.to_string()),
+        ("AttackRange_Melee", InItem.AttackRange_Melee.to_string()),
+        ("AttackRange_Ranged", InItem.AttackRange_Ranged.to_string()),
+        ("ProjectileSpeed", InItem.ProjectileSpeed.to_string()),
+        ("SpellAmp", InItem.SpellAmp.to_string()),
+        ("CastRange", InItem.CastRange.to_string()),
+        ("CooldownReduction", InItem.CooldownReduction.to_string()),
+        ("CleavePercent", InItem.OnHit.CleavePercent.to_string()),
+        ("CleaveRadius", InItem.OnHit.CleaveRadius.to_string()),
+        ("ChainChance", InItem.OnHit.ChainChance.to_string()),
+        ("ChainDamage", InItem.OnHit.ChainDamage.to_string()),
+        ("ChainStrikes", InItem.OnHit.ChainStrikes.to_string()),
+        ("BashChance_Melee", InItem.OnHit.BashChance_Melee.to_string()),
+        ("BashChance_Ranged", InItem.OnHit.BashChance_Ranged.to_string()),
+        ("BashDamage", InItem.OnHit.BashDamage.to_string()),
+        ("BashStun", InItem.OnHit.BashStun.to_string()),
+        ("TrueStrikeChance", InItem.OnHit.TrueStrikeChance.to_string()),
+        ("PierceDamage", InItem.OnHit.PierceDamage.to_string()),
+        ("SlowMoveSpeed_Melee", InItem.OnHit.SlowMoveSpeed_Melee.to_string()),
+        ("SlowMoveSpeed_Ranged", InItem.OnHit.SlowMoveSpeed_Ranged.to_string()),
+        ("SlowAttackSpeed_Melee", InItem.OnHit.SlowAttackSpeed_Melee.to_string()),
+        ("SlowAttackSpeed_Ranged", InItem.OnHit.SlowAttackSpeed_Ranged.to_string()),
+        ("SlowDuration", InItem.OnHit.SlowDuration.to_string()),
+        ("CorruptionDuration", InItem.OnHit.CorruptionDuration.to_string()),
+        ("Cooldown", InItem.Ability.as_ref().map_or(0.0, |ability| ability.Cooldown).to_string()),
+        ("ManaCost", InItem.Ability.as_ref().map_or(0.0, |ability| ability.ManaCost).to_string()),
+        ("Ability", InItem.Ability.as_ref().map(GetAbilityDescription).unwrap_or_default()),
+        ("NeutralTier", InItem.NeutralTier.to_string()),
+        ("AvailableMinute", InItem.AvailableMinute.to_string()),
+        ("IsInDropPool", InItem.IsInDropPool.to_string()),
+        ("IsConsumable", InItem.IsConsumable.to_string()),
+        ("Charges", InItem.Charges.to_string()),
+        ("IsPermanentWhenConsumed", InItem.IsPermanentWhenConsumed.to_string()),
+        ("IsConsumed", InItem.IsConsumed.to_string()),
+    ]
+}
+
+pub fn WriteItem(sw: &mut SheetWriter, InItem: &Item) -> std::io::Result<()>
+{
+    sw.append_row(Row::from_iter(ItemColumns(InItem).into_iter().map(|(_, value)| value)))
+}
+
+// Every item with components followed by its tree, one row per component with its stats. Multi level items have one tree.
+// Unlike Items this sheet is not read by Dota2BuyDps.xlsm, so it has a header row.
+pub fn WriteBuildTreeToXlsx(wb: &mut Workbook, InItems: &[Item]) -> Result<()>
+{
+    let mut sheet = wb.create_sheet("Build Tree");
+
+    wb.write_sheet(&mut sheet, |sheet_writer| {
+        let sw = sheet_writer;
+
+        let statColumns = ItemColumns(&Item::default()).into_iter().skip(1).map(|(column, _)| column.to_string());
+        sw.append_row(Row::from_iter(["Item", "Depth", "Component", "RecipeCost"].map(String::from).into_iter().chain(statColumns)))?;
+
+        for item in GetBuildTreeItems(InItems)
+        {
+            let tree = GetBuildTree(&item.Key, InItems);
+            for (depth, node) in FlattenBuildTree(&tree)
+            {
+                let mut cells = vec![item.Name.clone(), depth.to_string()];
+                match &node.Item
+                {
+                    Some(component) =>
+                    {
+                        cells.push(format!("{}{}", "  ".repeat(depth), GetItemDisplayName(component)));
+                        cells.push(component.RecipeCost.to_string());
+                        cells.extend(ItemColumns(component).into_iter().skip(1).map(|(_, value)| value));
+                    }
+                    None => cells.push(format!("{}{}", "  ".repeat(depth), node.Key)),
+                }
+                sw.append_row(Row::from_iter(cells.into_iter()))?;
+            }
+        }
+
+        Ok(())
+    }).map_err(SheetError("Build Tree"))
+}
+
+// Neutral items grouped by tier, with a header row like Build Tree
+pub fn WriteNeutralItemsToXlsx(wb: &mut Workbook, InItems: &[Item]) -> Result<()>
+{
+    let mut sheet = wb.create_sheet("Neutral Items");
+
+    let mut neutralItems: Vec<&Item> = InItems.iter().filter(|item| item.IsNeutralItem).collect();
+    neutralItems.sort_by_key(|item| (item.NeutralTier, GetItemDisplayName(item)));
+
+    wb.write_sheet(&mut sheet, |sheet_writer| {
+        let sw = sheet_writer;
+
+        sw.append_row(Row::from_iter(ItemColumns(&Item::default()).into_iter().map(|(column, _)| column)))?;
+
+        let mut tier = None;
+        for item in neutralItems
+        {
+            // Empty row between tiers
+            if tier.is_some_and(|tier| tier != item.NeutralTier)
+            {
+                sw.append_blank_rows(1);
+            }
+            tier = Some(item.NeutralTier);
+            WriteItem(sw, item)?;
+        }
+
+        Ok(())
+    }).map_err(SheetError("Neutral Items"))
+}
+
+pub fn WriteHeroesToXlsx(wb: &mut Workbook, InHeroes: &[Hero]) -> Result<()>
+{
+    // Create Sheet
+    let mut sheet = wb.create_sheet("Heroes");
+
+    // Write to Sheet
+    wb.write_sheet(&mut sheet, |sheet_writer| {
+        let sw = sheet_writer;
+        
+        for hero in InHeroes
+        {
+            sw.append_row(row![
+                hero.Name.clone(),
+                hero.PrimaryAttribute.DataName().to_string(),
+                hero.AttackType.to_string(),
+                hero.BAT.to_string(),
+                hero.BaseAttackSpeed.to_string(),
+                hero.BaseStr.to_string(),
+                hero.BaseAgi.to_string(),
+                hero.BaseInt.to_string(),
+                hero.StrGain.to_string(),
+                hero.AgiGain.to_string(),
+                hero.IntGain.to_string(),
+                hero.BaseAttackMin.to_string(),
+                hero.BaseAttackMax.to_string(),
+                hero.BaseArmor.to_string(),
+                hero.BaseMagicResist.to_string(),
+                hero.AttackRange.to_string(),
+                hero.ProjectileSpeed.to_string(),
+                hero.MoveSpeed.to_string(),
+                hero.TurnRate.to_string(),
+                hero.BaseHealth.to_string(),
+                hero.BaseHealthRegen.to_string(),
+                hero.BaseMana.to_string(),
+                hero.BaseManaRegen.to_string(),
+                hero.AttackPoint.to_string()
+            ])?;
+        }
+
+        Ok(())
+    }).map_err(SheetError("Heroes"))
+}
+
+// Every hero without items at each of SHEET_LEVELS, with a header row
+pub fn WriteHeroLevelsToXlsx(wb: &mut Workbook, InHeroes: &[Hero]) -> Result<()>
+{
+    let mut sheet = wb.create_sheet("Hero Levels");
+
+    wb.write_sheet(&mut sheet, |sheet_writer| {
+        let sw = sheet_writer;
+
+        sw.append_row(row!["Name", "Level", "Str", "Agi", "Int", "Damage", "AttackSpeed", "AttacksPerSecond", "Armor",
+            "Health", "HealthRegen", "Mana", "ManaRegen", "MagicResist"])?;
+
+        for hero in InHeroes
+        {
+            for level in SHEET_LEVELS
+            {
+                let stats = GetHeroStatsAtLevel(hero, level);
+                sw.append_row(row![
+                    hero.Name.clone(),
+                    stats.Level.to_string(),
+                    stats.Str.to_string(),
+                    stats.Agi.to_string(),
+                    stats.Int.to_string(),
+                    stats.Damage.to_string(),
+                    stats.AttackSpeed.to_string(),
+                    stats.AttacksPerSecond.to_string(),
+                    stats.Armor.to_string(),
+                    stats.Health.to_string(),
+                    stats.HealthRegen.to_string(),
+                    stats.Mana.to_string(),
+                    stats.ManaRegen.to_string(),
+                    stats.MagicResist.to_string()
+                ])?;
+            }
+        }
+
+        Ok(())
+    }).map_err(SheetError("Hero Levels"))
+}
+
+// When each hero's attacks land at level 1 against a target at its attack range, with a header row
+pub fn WriteHeroTimingsToXlsx(wb: &mut Workbook, InHeroes: &[Hero]) -> Result<()>
+{
+    let mut sheet = wb.create_sheet("Hero Timings");
+
+    wb.write_sheet(&mut sheet, |sheet_writer| {
+        let sw = sheet_writer;
+
+        let columns = ["Name", "AttackType", "AttackPoint", "ProjectileSpeed", "Distance", "AttackSpeed",
+            "AttackPointTime", "Interval", "TravelTime"];
+        sw.append_row(Row::from_iter(columns.map(String::from).into_iter().chain(SHEET_HITS.map(|hit| format!("Hit{}", hit)))))?;
+
+        for hero in InHeroes
+        {
+            let attackSpeed = GetHeroStatsAtLevel(hero, 1).AttackSpeed;
+            let distance = hero.AttackRange as f32;
+            let timings = GetAttackTimings(hero, attackSpeed, distance);
+
+            let mut cells = vec![
+                hero.Name.clone(),
+                hero.AttackType.clone(),
+                hero.AttackPoint.to_string(),
+                hero.ProjectileSpeed.to_string(),
+                distance.to_string(),
+                attackSpeed.to_string(),
+                timings.AttackPoint.to_string(),
+                timings.Interval.to_string(),
+                timings.TravelTime.to_string(),
+            ];
+            cells.extend(SHEET_HITS.map(|hit| GetHitTime(hero, attackSpeed, distance, hit).to_string()));
+            sw.append_row(Row::from_iter(cells.into_iter()))?;
+        }
+
+        sw.append_blank_rows(1);
+        sw.append_row(row!["Neither data source has the attack backswing, so the times only cover the attack point, the time between attacks and the projectile flight"])?;
+
+        Ok(())
+    }).map_err(SheetError("Hero Timings"))
+}
@@ -0,0 +1,246 @@
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::cache::{self, CacheStatus};
+use crate::error::{Error, IoError, Result};
+use crate::kv;
+
+// Where the json data is read from
+pub enum DataSource
+{
+    // Download from github, through the http cache in the given directory if any
+    Online(Option<PathBuf>),
+    // Read snapshots from a local directory
+    Offline(PathBuf),
+}
+
+// A json file we depend on, both its url and its path inside a snapshot directory
+pub struct DataFile
+{
+    pub Url: &'static str,
+    pub SnapshotPath: &'static str,
+}
+
+pub const ITEM_DATA_FILE: DataFile = DataFile
+{
+    Url: "https://raw.githubusercontent.com/dotabuff/d2vpkr/master/dota/scripts/npc/items.json",
+    SnapshotPath: "d2vpkr/items.json",
+};
+
+pub const HERO_DATA_FILE: DataFile = DataFile
+{
+    Url: "https://raw.githubusercontent.com/odota/dotaconstants/master/build/heroes.json",
+    SnapshotPath: "dotaconstants/heroes.json",
+};
+
+pub const ITEM_NAMES_FILE: DataFile = DataFile
+{
+    Url: "https://raw.githubusercontent.com/odota/dotaconstants/master/build/items.json",
+    SnapshotPath: "dotaconstants/items.json",
+};
+
+pub const DATA_FILES: [&DataFile; 3] = [&ITEM_DATA_FILE, &HERO_DATA_FILE, &ITEM_NAMES_FILE];
+
+// The json and, when it went through the http cache, whether it changed
+pub fn GetDataJsonString(Source: &DataSource, File: &DataFile) -> Result<(String, Option<CacheStatus>)>
+{
+    match Source
+    {
+        DataSource::Online(None) =>
+        {
+            // Get html source
+            let networkError = |e| Error::Network { Url: File.Url.to_string(), Source: e };
+            let response = reqwest::blocking::get(File.Url).and_then(|r| r.error_for_status()).map_err(networkError)?;
+            Ok((response.text().map_err(networkError)?, None))
+        }
+        DataSource::Online(Some(cacheDir)) =>
+        {
+            let (json, status) = cache::GetCached(cacheDir, File.Url, File.SnapshotPath)?;
+            Ok((json, Some(status)))
+        }
+        DataSource::Offline(dir) =>
+        {
+            let path = dir.join(File.SnapshotPath);
+            Ok((std::fs::read_to_string(&path).map_err(IoError(&path))?, None))
+        }
+    }
+}
+
+// Copies every data file into Dir, so it can later be used with --offline. Returns where each file went.
+pub fn SaveSnapshot(Source: &DataSource, Dir: &Path) -> Result<Vec<(&'static DataFile, Option<CacheStatus>, PathBuf)>>
+{
+    let mut saved = Vec::new();
+    for file in DATA_FILES
+    {
+        let (json, status) = GetDataJsonString(Source, file)?;
+        let path = Dir.join(file.SnapshotPath);
+        let parent = path.parent().unwrap();
+
+        std::fs::create_dir_all(parent).map_err(IoError(parent))?;
+        std::fs::write(&path, json).map_err(IoError(&path))?;
+        saved.push((file, status, path));
+    }
+    Ok(saved)
+}
+
+pub fn GetItemDataJsonString(Source: &DataSource) -> Result<(String, Option<CacheStatus>)>
+{
+    GetDataJsonString(Source, &ITEM_DATA_FILE)
+}
+
+pub fn GetHeroDataJsonString(Source: &DataSource) -> Result<(String, Option<CacheStatus>)>
+{
+    GetDataJsonString(Source, &HERO_DATA_FILE)
+}
+
+pub fn GetItemNamesJsonString(Source: &DataSource) -> Result<(String, Option<CacheStatus>)>
+{
+    GetDataJsonString(Source, &ITEM_NAMES_FILE)
+}
+
+// Finds a file from scripts/npc, Dir can be the game/vpk root or the npc directory itself
+fn FindKvFile(Dir: &Path, FileName: &str) -> PathBuf
+{
+    let nested = Dir.join("scripts").join("npc").join(FileName);
+    if nested.exists()
+    {
+        nested
+    }
+    else
+    {
+        Dir.join(FileName)
+    }
+}
+
+// Reads items.txt, its root "DOTAAbilities" block has the same shape as d2vpkr's items.json
+pub fn GetKvItemDataJsonString(Dir: &Path) -> Result<String>
+{
+    let items = kv::LoadKeyValuesFile(&FindKvFile(Dir, "items.txt"))?;
+    Ok(serde_json::to_string(&items).unwrap())
+}
+
+// Reads npc_heroes.txt and converts it to the dotaconstants heroes.json shape
+pub fn GetKvHeroDataJsonString(Dir: &Path) -> Result<String>
+{
+    let root = kv::LoadKeyValuesFile(&FindKvFile(Dir, "npc_heroes.txt"))?;
+    let allHeroes = root.get("DOTAHeroes").and_then(|v| v.as_object())
+        .ok_or_else(|| Error::Schema { Key: "npc_heroes.txt".to_string(), Path: String::new(), Message: "no DOTAHeroes block".to_string() })?;
+
+    // Every hero inherits the values it does not set from the base hero
+    let emptyMap = Map::new();
+    let baseHero = allHeroes.get("npc_dota_hero_base").and_then(|v| v.as_object()).unwrap_or(&emptyMap);
+
+    let mut heroes: Map<String, Value> = Map::new();
+    for (key, hero) in allHeroes
+    {
+        let heroMap = match hero.as_object()
+        {
+            Some(heroMap) if key != "npc_dota_hero_base" && heroMap.contains_key("HeroID") => heroMap,
+            _ => continue,
+        };
+
+        let get = |name: &str| -> String
+        {
+            heroMap.get(name).or(baseHero.get(name)).and_then(|v| v.as_str()).unwrap_or("").to_string()
+        };
+
+        let localizedName = match heroMap.get("workshop_guide_name").and_then(|v| v.as_str())
+        {
+            Some(name) => name.to_string(),
+            // No display name in the file, make one from the key: npc_dota_hero_shadow_shaman -> Shadow Shaman
+            None => key.trim_start_matches("npc_dota_hero_").split('_').map(|word|
+            {
+                let mut chars = word.chars();
+                chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+            }).collect::<Vec<String>>().join(" "),
+        };
+
+        // Unknown values are kept as they are so reading the heroes can report them
+        let primaryAttr = match get("AttributePrimary").as_str()
+        {
+            "DOTA_ATTRIBUTE_STRENGTH" => "str".to_string(),
+            "DOTA_ATTRIBUTE_AGILITY" => "agi".to_string(),
+            "DOTA_ATTRIBUTE_INTELLECT" => "int".to_string(),
+            "DOTA_ATTRIBUTE_ALL" => "all".to_string(),
+            other => other.to_string(),
+        };
+
+        let attackType = if get("AttackCapabilities") == "DOTA_UNIT_CAP_RANGED_ATTACK" { "Ranged" } else { "Melee" };
+        let attackRate = get("AttackRate").parse::<f64>().unwrap_or(1.7);
+        // null when the hero and the base hero leave it out
+        let number = |name: &str| get(name).parse::<f64>().ok();
+
+        heroes.insert(get("HeroID"), serde_json::json!({
+            "name": key,
+            "localized_name": localizedName,
+            "primary_attr": primaryAttr,
+            "attack_type": attackType,
+            "attack_rate": attackRate,
+            // dotaconstants calls the base attack speed base_attack_time
+            "base_attack_time": number("BaseAttackSpeed"),
+            "base_str": number("AttributeBaseStrength"),
+            "base_agi": number("AttributeBaseAgility"),
+            "base_int": number("AttributeBaseIntelligence"),
+            "str_gain": number("AttributeStrengthGain"),
+            "agi_gain": number("AttributeAgilityGain"),
+            "int_gain": number("AttributeIntelligenceGain"),
+            "base_attack_min": number("AttackDamageMin"),
+            "base_attack_max": number("AttackDamageMax"),
+            "base_armor": number("ArmorPhysical"),
+            "base_mr": number("MagicalResistance"),
+            "attack_range": number("AttackRange"),
+            "projectile_speed": number("ProjectileSpeed"),
+            "attack_point": number("AttackAnimationPoint"),
+            "move_speed": number("MovementSpeed"),
+            "turn_rate": number("MovementTurnRate"),
+            "base_health": number("StatusHealth"),
+            "base_health_regen": number("StatusHealthRegen"),
+            "base_mana": number("StatusMana"),
+            "base_mana_regen": number("StatusManaRegen"),
+        }));
+    }
+
+    Ok(serde_json::to_string(&heroes).unwrap())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // An empty directory of its own for each test
+    fn TestDir(Name: &str) -> PathBuf
+    {
+        let dir = std::env::temp_dir().join(format!("Dota2Items-fetch-{}-{}", Name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn SnapshotRoundTrip()
+    {
+        let source = TestDir("source");
+        for file in DATA_FILES
+        {
+            let path = source.join(file.SnapshotPath);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, format!("{{\"file\": \"{}\"}}", file.SnapshotPath)).unwrap();
+        }
+
+        let snapshot = TestDir("snapshot");
+        let saved = SaveSnapshot(&DataSource::Offline(source.clone()), &snapshot).unwrap();
+        assert_eq!(saved.len(), DATA_FILES.len());
+
+        let offline = DataSource::Offline(snapshot.clone());
+        assert_eq!(GetItemDataJsonString(&offline).unwrap(), ("{\"file\": \"d2vpkr/items.json\"}".to_string(), None));
+        assert_eq!(GetHeroDataJsonString(&offline).unwrap().0, "{\"file\": \"dotaconstants/heroes.json\"}");
+        assert_eq!(GetItemNamesJsonString(&offline).unwrap().0, "{\"file\": \"dotaconstants/items.json\"}");
+
+        // A missing snapshot file is an error naming the file, not a panic
+        std::fs::remove_file(snapshot.join(HERO_DATA_FILE.SnapshotPath)).unwrap();
+        assert!(GetHeroDataJsonString(&offline).unwrap_err().to_string().contains("heroes.json"));
+
+        let _ = std::fs::remove_dir_all(&source);
+        let _ = std::fs::remove_dir_all(&snapshot);
+    }
+}
@@ -0,0 +1,121 @@
+// A hero's stats at a given level, from its base stats and attribute gains
+
+use crate::dps::{AttacksPerSecond, PrimaryAttributeDamage};
+use crate::model::Hero;
+
+pub const MAX_LEVEL: i32 = 30;
+
+// Levels the spreadsheet shows
+pub const SHEET_LEVELS: [i32; 6] = [1, 6, 12, 18, 25, 30];
+
+// Attribute Bonus gives every attribute this much at each of these levels
+pub const ATTRIBUTE_BONUS: f32 = 2.0;
+pub const ATTRIBUTE_BONUS_LEVELS: [i32; 7] = [17, 19, 21, 22, 23, 24, 26];
+
+// What one point of an attribute gives
+pub const HEALTH_PER_STR: f32 = 22.0;
+pub const HEALTH_REGEN_PER_STR: f32 = 0.1;
+pub const ARMOR_PER_AGI: f32 = 1.0 / 6.0;
+pub const ATTACK_SPEED_PER_AGI: f32 = 1.0;
+pub const MANA_PER_INT: f32 = 12.0;
+pub const MANA_REGEN_PER_INT: f32 = 0.05;
+pub const MAGIC_RESIST_PER_INT: f32 = 0.001;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HeroStats
+{
+    pub Level: i32,
+    pub Str: f32,
+    pub Agi: f32,
+    pub Int: f32,
+    // Average attack damage, attribute damage included
+    pub Damage: f32,
+    pub AttackSpeed: f32,
+    pub AttacksPerSecond: f32,
+    pub Armor: f32,
+    pub Health: f32,
+    pub HealthRegen: f32,
+    pub Mana: f32,
+    pub ManaRegen: f32,
+    // 0-1, base magic resistance and intelligence stack multiplicatively
+    pub MagicResist: f32,
+}
+
+// InHero's stats without items at Level, which is kept within 1-30
+pub fn GetHeroStatsAtLevel(InHero: &Hero, Level: i32) -> HeroStats
+{
+    let level = Level.clamp(1, MAX_LEVEL);
+    let bonusLevels = ATTRIBUTE_BONUS_LEVELS.iter().filter(|bonusLevel| **bonusLevel <= level).count();
+    let bonus = bonusLevels as f32 * ATTRIBUTE_BONUS;
+
+    let growth = (level - 1) as f32;
+    let str = InHero.BaseStr + InHero.StrGain * growth + bonus;
+    let agi = InHero.BaseAgi + InHero.AgiGain * growth + bonus;
+    let int = InHero.BaseInt + InHero.IntGain * growth + bonus;
+
+    let attackSpeed = InHero.BaseAttackSpeed as f32 + agi * ATTACK_SPEED_PER_AGI;
+
+    HeroStats
+    {
+        Level: level,
+        Str: str,
+        Agi: agi,
+        Int: int,
+        Damage: (InHero.BaseAttackMin + InHero.BaseAttackMax) as f32 / 2.0 + PrimaryAttributeDamage(InHero, str, agi, int),
+        AttackSpeed: attackSpeed,
+        AttacksPerSecond: AttacksPerSecond(InHero.BAT, attackSpeed),
+        Armor: InHero.BaseArmor + agi * ARMOR_PER_AGI,
+        Health: InHero.BaseHealth + str * HEALTH_PER_STR,
+        HealthRegen: InHero.BaseHealthRegen + str * HEALTH_REGEN_PER_STR,
+        Mana: InHero.BaseMana + int * MANA_PER_INT,
+        ManaRegen: InHero.BaseManaRegen + int * MANA_REGEN_PER_INT,
+        MagicResist: 1.0 - (1.0 - InHero.BaseMagicResist) * (1.0 - int * MAGIC_RESIST_PER_INT),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::model::PrimaryAttribute;
+    use crate::testutil::{Close, TestHero};
+
+    #[test]
+    fn LevelOne()
+    {
+        let stats = GetHeroStatsAtLevel(&TestHero(), 1);
+        assert_eq!((stats.Str, stats.Agi, stats.Int), (20.0, 20.0, 20.0));
+        // 32 average + 20 agility
+        assert!(Close(stats.Damage, 52.0));
+        assert!(Close(stats.AttackSpeed, 120.0));
+        assert!(Close(stats.Health, 120.0 + 20.0 * 22.0));
+        assert!(Close(stats.Armor, 1.0 + 20.0 / 6.0));
+        // 1 - 0.75 * 0.98
+        assert!(Close(stats.MagicResist, 0.265));
+    }
+
+    #[test]
+    fn LevelTwentyFiveWithAttributeBonus()
+    {
+        // 24 levels of gain and 6 Attribute Bonus levels (17, 19, 21, 22, 23, 24)
+        let stats = GetHeroStatsAtLevel(&Hero{PrimaryAttribute: PrimaryAttribute::Strength, ..TestHero()}, 25);
+        assert!(Close(stats.Str, 20.0 + 2.0 * 24.0 + 12.0));
+        assert!(Close(stats.Agi, 20.0 + 3.0 * 24.0 + 12.0));
+        assert!(Close(stats.Damage, 32.0 + stats.Str));
+        assert!(Close(stats.ManaRegen, stats.Int * 0.05));
+    }
+
+    #[test]
+    fn UniversalHeroesGetPartOfEveryAttribute()
+    {
+        let stats = GetHeroStatsAtLevel(&Hero{PrimaryAttribute: PrimaryAttribute::Universal, ..TestHero()}, 1);
+        assert!(Close(stats.Damage, 32.0 + 0.7 * 60.0));
+    }
+
+    #[test]
+    fn LevelIsClamped()
+    {
+        assert_eq!(GetHeroStatsAtLevel(&TestHero(), 99), GetHeroStatsAtLevel(&TestHero(), 30));
+        assert_eq!(GetHeroStatsAtLevel(&TestHero(), 0).Level, 1);
+    }
+}
@@ -0,0 +1,362 @@
+// Parser for Valve KeyValues (KV1) text files, like scripts/npc/items.txt and npc_heroes.txt.
+// Blocks become json objects and values stay strings, the same shape as d2vpkr's json conversion.
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub struct KvError
+{
+    pub File: String,
+    pub Line: usize,
+    pub Message: String,
+}
+
+impl fmt::Display for KvError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}:{}: {}", self.File, self.Line, self.Message)
+    }
+}
+
+impl std::error::Error for KvError {}
+
+#[derive(Debug, PartialEq)]
+enum Token
+{
+    Str(String),
+    Open,
+    Close,
+    // Platform conditional like [$WIN32], ignored
+    Conditional,
+}
+
+fn Tokenize(Text: &str, File: &str) -> Result<Vec<(Token, usize)>, KvError>
+{
+    let mut tokens = Vec::new();
+    let mut chars = Text.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next()
+    {
+        match c
+        {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') =>
+            {
+                // Comment until end of line
+                for c in chars.by_ref()
+                {
+                    if c == '\n'
+                    {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '{' => tokens.push((Token::Open, line)),
+            '}' => tokens.push((Token::Close, line)),
+            '[' =>
+            {
+                let startLine = line;
+                loop
+                {
+                    match chars.next()
+                    {
+                        Some(']') => break,
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(KvError{File: File.to_string(), Line: startLine, Message: "unterminated conditional".to_string()}),
+                    }
+                }
+                tokens.push((Token::Conditional, startLine));
+            }
+            '"' =>
+            {
+                let startLine = line;
+                let mut text = String::new();
+                loop
+                {
+                    match chars.next()
+                    {
+                        // Like Valve's reader for the npc scripts there are no escapes, a backslash is kept as is
+                        Some('"') => break,
+                        Some(c) =>
+                        {
+                            if c == '\n'
+                            {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                        None => return Err(KvError{File: File.to_string(), Line: startLine, Message: "unterminated string".to_string()}),
+                    }
+                }
+                tokens.push((Token::Str(text), startLine));
+            }
+            c =>
+            {
+                // Unquoted token
+                let mut text = c.to_string();
+                while let Some(&next) = chars.peek()
+                {
+                    if next.is_whitespace() || next == '{' || next == '}' || next == '"'
+                    {
+                        break;
+                    }
+                    text.push(next);
+                    chars.next();
+                }
+                tokens.push((Token::Str(text), line));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Inserts a value, merging blocks when the key already exists. For plain values the last one wins.
+fn MergeInto(Target: &mut Map<String, Value>, Key: String, NewValue: Value)
+{
+    match (Target.get_mut(&Key), NewValue)
+    {
+        (Some(Value::Object(existing)), Value::Object(newMap)) =>
+        {
+            for (k, v) in newMap
+            {
+                MergeInto(existing, k, v);
+            }
+        }
+        (_, newValue) =>
+        {
+            Target.insert(Key, newValue);
+        }
+    }
+}
+
+struct Parser<'a>
+{
+    Tokens: Vec<(Token, usize)>,
+    Pos: usize,
+    File: &'a str,
+    Bases: Vec<String>,
+}
+
+impl Parser<'_>
+{
+    fn Error(&self, Message: &str) -> KvError
+    {
+        let line = self.Tokens.get(self.Pos).or(self.Tokens.last()).map(|t| t.1).unwrap_or(1);
+        KvError{File: self.File.to_string(), Line: line, Message: Message.to_string()}
+    }
+
+    fn SkipConditional(&mut self)
+    {
+        while let Some((Token::Conditional, _)) = self.Tokens.get(self.Pos)
+        {
+            self.Pos += 1;
+        }
+    }
+
+    fn ParseBlock(&mut self, IsRoot: bool) -> Result<Map<String, Value>, KvError>
+    {
+        let mut map = Map::new();
+
+        loop
+        {
+            self.SkipConditional();
+
+            let key = match self.Tokens.get(self.Pos)
+            {
+                None if IsRoot => return Ok(map),
+                None => return Err(self.Error("unexpected end of file, missing '}'")),
+                Some((Token::Close, _)) if !IsRoot =>
+                {
+                    self.Pos += 1;
+                    return Ok(map);
+                }
+                Some((Token::Str(key), _)) => key.clone(),
+                Some(_) => return Err(self.Error("expected a key")),
+            };
+            self.Pos += 1;
+            self.SkipConditional();
+
+            let value = match self.Tokens.get(self.Pos)
+            {
+                Some((Token::Str(value), _)) =>
+                {
+                    let value = value.clone();
+                    self.Pos += 1;
+                    Value::String(value)
+                }
+                Some((Token::Open, _)) =>
+                {
+                    self.Pos += 1;
+                    Value::Object(self.ParseBlock(false)?)
+                }
+                _ => return Err(self.Error(&format!("expected a value for \"{}\"", key))),
+            };
+
+            if IsRoot && (key == "#base" || key == "#include")
+            {
+                if let Value::String(file) = value
+                {
+                    self.Bases.push(file);
+                }
+                continue;
+            }
+
+            MergeInto(&mut map, key, value);
+        }
+    }
+}
+
+// Parses KeyValues text. Returns the root block and the files referenced by #base.
+pub fn ParseKeyValues(Text: &str, File: &str) -> Result<(Map<String, Value>, Vec<String>), KvError>
+{
+    let tokens = Tokenize(Text, File)?;
+    let mut parser = Parser{Tokens: tokens, Pos: 0, File, Bases: Vec::new()};
+    let root = parser.ParseBlock(true)?;
+    Ok((root, parser.Bases))
+}
+
+fn LoadKeyValuesFileOnPath(FilePath: &Path, Loading: &mut Vec<PathBuf>) -> Result<Map<String, Value>, KvError>
+{
+    let fileName = FilePath.display().to_string();
+    let text = std::fs::read_to_string(FilePath)
+        .map_err(|e| KvError{File: fileName.clone(), Line: 0, Message: e.to_string()})?;
+
+    // Guards against files that #base each other
+    let canonicalPath = FilePath.canonicalize().unwrap_or(FilePath.to_path_buf());
+    if Loading.contains(&canonicalPath)
+    {
+        return Err(KvError{File: fileName, Line: 0, Message: "#base cycle".to_string()});
+    }
+
+    let (root, bases) = ParseKeyValues(&text, &fileName)?;
+
+    Loading.push(canonicalPath);
+    let dir = FilePath.parent().unwrap_or(Path::new("."));
+    let mut merged = Map::new();
+    for base in bases
+    {
+        for (k, v) in LoadKeyValuesFileOnPath(&dir.join(base), Loading)?
+        {
+            MergeInto(&mut merged, k, v);
+        }
+    }
+    Loading.pop();
+
+    for (k, v) in root
+    {
+        MergeInto(&mut merged, k, v);
+    }
+
+    Ok(merged)
+}
+
+// Reads a KeyValues file and everything it pulls in with #base, relative to the file's directory.
+// Keys in the file itself override the ones from its bases.
+pub fn LoadKeyValuesFile(FilePath: &Path) -> Result<Map<String, Value>, KvError>
+{
+    LoadKeyValuesFileOnPath(FilePath, &mut Vec::new())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::schema::AbilityEntry;
+
+    fn Parse(Text: &str) -> Value
+    {
+        Value::Object(ParseKeyValues(Text, "test.txt").unwrap().0)
+    }
+
+    fn FixturePath(FileName: &str) -> PathBuf
+    {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/kv").join(FileName)
+    }
+
+    #[test]
+    fn CommentsAndDuplicateKeys()
+    {
+        let root = Parse(r#"
+            // A comment line
+            "Root"
+            {
+                "A"     "1"     // Trailing comment
+                "A"     "2"
+                "Block" { "X" "1" "Y" "1" }
+                "Block" { "Y" "2" "Z" "2" }
+            }
+        "#);
+
+        // The last plain value wins and blocks with the same key are merged
+        assert_eq!(root, serde_json::json!({"Root": {"A": "2", "Block": {"X": "1", "Y": "2", "Z": "2"}}}));
+    }
+
+    #[test]
+    fn BackslashesConditionalsAndUnquotedTokens()
+    {
+        let root = Parse(r#"
+            "Root"
+            {
+                "Path"      "C:\dota\"
+                "Text"      "one\ntwo"
+                "Windows"   "1"     [$WIN32]
+                [$OSX] "Mac" "2"
+                Unquoted    value
+            }
+        "#);
+        assert_eq!(root, serde_json::json!({"Root": {"Path": "C:\\dota\\", "Text": "one\\ntwo", "Windows": "1", "Mac": "2", "Unquoted": "value"}}));
+    }
+
+    #[test]
+    fn ReportsErrorLines()
+    {
+        let unterminated = ParseKeyValues("\"Root\"\n{\n    \"A\" \"oops\n}\n", "test.txt").unwrap_err();
+        assert_eq!(unterminated.to_string(), "test.txt:3: unterminated string");
+
+        let unclosed = ParseKeyValues("\"Root\"\n{\n    \"A\" \"1\"\n", "test.txt").unwrap_err();
+        assert_eq!(unclosed.to_string(), "test.txt:3: unexpected end of file, missing '}'");
+    }
+
+    #[test]
+    fn AbilitySpecialBlocks()
+    {
+        let root = Parse(r#"
+            "item_test"
+            {
+                "AbilitySpecial"
+                {
+                    "01" { "var_type" "FIELD_INTEGER" "bonus_damage" "10 20" }
+                    "02" { "var_type" "FIELD_FLOAT"   "bonus_armor"  "2.5" }
+                }
+            }
+        "#);
+
+        let entry: AbilityEntry = serde_json::from_value(root["item_test"].clone()).unwrap();
+        assert_eq!(Value::Object(entry.Specials()), serde_json::json!({"bonus_damage": "10 20", "bonus_armor": "2.5"}));
+    }
+
+    #[test]
+    fn BaseFilesAreMergedUnderTheFile()
+    {
+        let root = Value::Object(LoadKeyValuesFile(&FixturePath("items.txt")).unwrap());
+        assert_eq!(root, serde_json::json!({"DOTAAbilities": {
+            "item_test": {"ItemCost": "500", "ItemShopTags": "damage", "ItemQuality": "consumable"},
+            "item_base_only": {"ItemCost": "50"},
+        }}));
+    }
+
+    #[test]
+    fn BaseCycleIsAnError()
+    {
+        let error = LoadKeyValuesFile(&FixturePath("cycle_a.txt")).unwrap_err();
+        assert_eq!(error.Message, "#base cycle");
+    }
+}
@@ -0,0 +1,86 @@
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+// The interactive DPS spreadsheet that reads the data spreadsheet
+pub const DPS_SPREADSHEET: &str = "Dota2BuyDps.xlsm";
+
+// What opens the spreadsheets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opener
+{
+    // The platform's file opener, so whatever program is registered for .xlsx
+    Default,
+    LibreOffice,
+}
+
+fn LaunchError(Program: &str, e: std::io::Error) -> Error
+{
+    Error::Launch { Program: Program.to_string(), Source: e }
+}
+
+// Runs an opener that returns once the file is handed off, failing on a non-zero exit
+fn RunOpener(Program: &str, Args: &[&str]) -> Result<()>
+{
+    let output = Command::new(Program).args(Args).output().map_err(|e| LaunchError(Program, e))?;
+
+    if !output.status.success()
+    {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if stderr.is_empty() { output.status.to_string() } else { format!("{}: {}", output.status, stderr) };
+        return Err(LaunchError(Program, std::io::Error::other(message)));
+    }
+    Ok(())
+}
+
+// Starts a program without waiting for it to exit
+fn Spawn(Program: &str, Args: &[&str]) -> Result<()>
+{
+    Command::new(Program).args(Args).spawn().map(|_| ()).map_err(|e| LaunchError(Program, e))
+}
+
+fn OpenFile(FilePath: &str, InOpener: Opener) -> Result<()>
+{
+    match InOpener
+    {
+        Opener::Default if cfg!(target_os = "windows") =>
+        {
+            // The empty string is the window title, start takes the first quoted argument as one
+            RunOpener("cmd", &["/C", "start", "", FilePath])
+        }
+        Opener::Default if cfg!(target_os = "macos") => RunOpener("open", &[FilePath]),
+        Opener::Default =>
+        {
+            // Not every desktop has xdg-open, gio is the GNOME fallback
+            match RunOpener("xdg-open", &[FilePath])
+            {
+                Err(Error::Launch { Source, .. }) if Source.kind() == ErrorKind::NotFound => RunOpener("gio", &["open", FilePath]),
+                result => result,
+            }
+        }
+        Opener::LibreOffice if cfg!(target_os = "macos") => RunOpener("open", &["-a", "LibreOffice", FilePath]),
+        Opener::LibreOffice => Spawn("soffice", &[FilePath]),
+    }
+}
+
+// Opens DataFile and, when it is there, Dota2BuyDps.xlsm. Returns a warning when Dota2BuyDps.xlsm is missing.
+pub fn OpenExcel(DataFile: &str, InOpener: Opener) -> Result<Vec<String>>
+{
+    if !Path::new(DataFile).exists()
+    {
+        return Err(Error::Io { Path: DataFile.into(), Source: std::io::Error::new(ErrorKind::NotFound, "spreadsheet not found") });
+    }
+    OpenFile(DataFile, InOpener)?;
+
+    if Path::new(DPS_SPREADSHEET).exists()
+    {
+        OpenFile(DPS_SPREADSHEET, InOpener)?;
+        Ok(Vec::new())
+    }
+    else
+    {
+        Ok(vec![format!("{} not found, only opening {}", DPS_SPREADSHEET, DataFile)])
+    }
+}
@@ -0,0 +1,30 @@
+#![allow(non_snake_case)]
+
+// Fetches dota 2 item and hero data and turns it into Item/Hero lists and a spreadsheet.
+//
+// From raw json to items and heroes, without touching Excel:
+//     let mut items = Vec::new();
+//     let report = parse::GetItemStats(&itemsJson, &overrides::Overrides::Builtin(), &mut items, true)?;
+//     parse::GetRealItemNames(&itemNamesJson, &mut items)?;
+
+pub mod buildtree;
+pub mod cache;
+pub mod diff;
+pub mod dps;
+pub mod error;
+pub mod export;
+pub mod fetch;
+pub mod herostats;
+pub mod kv;
+pub mod launcher;
+pub mod model;
+pub mod overrides;
+pub mod parse;
+pub mod schema;
+pub mod stacking;
+pub mod timings;
+#[cfg(test)]
+mod testutil;
+
+pub use error::{Error, Result};
+pub use model::{Hero, Item};
@@ -1,776 +1,464 @@
-extern crate simple_excel_writer as excel;
-use excel::*;
-use scraper::{Html, Selector};
+#![allow(non_snake_case)]
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use Dota2Items::cache::CacheStatus;
+use Dota2Items::diff::{DiffItems, ItemChange};
+use Dota2Items::dps::{DpsAgainst, DpsPerThousandGold, HeroBaseDamage};
+use Dota2Items::error::{Error, Result};
+use Dota2Items::export::{CloseWorkbook, WriteBuildTreeToXlsx, WriteHeroLevelsToXlsx, WriteHeroTimingsToXlsx, WriteHeroesToXlsx, WriteItemsToXlsx, WriteNeutralItemsToXlsx};
+use Dota2Items::fetch::*;
+use Dota2Items::launcher::{OpenExcel, Opener};
+use Dota2Items::model::GetItemDisplayName;
+use Dota2Items::overrides::{Overrides, OVERRIDES_FILE};
+use Dota2Items::parse::{GetHeroesData, GetItemStats, GetRealItemNames};
+use Dota2Items::{Hero, Item};
+
+use simple_excel_writer::Workbook;
+use std::path::PathBuf;
+
+const DEFAULT_CACHE_DIR: &str = "cache";
+const OUTPUT_FILE: &str = "Dota2Data.xlsx";
+
+/// Fetches dota 2 items/heroes and creates a spreadsheet with data.
+#[derive(Parser)]
+#[command(name = "Dota2Items")]
+struct Cli
+{
+    #[command(flatten)]
+    Source: SourceArgs,
 
-use serde_json::{Map, Value};
-use std::process::Command;
+    /// Defaults to `export` when left out
+    #[command(subcommand)]
+    Command: Option<CliCommand>,
+}
 
-#[derive(Debug)]
-struct ItemEntry
+// Where the data comes from, shared by all subcommands
+#[derive(Args)]
+struct SourceArgs
 {
-    Name: String,
-    Category: String
+    /// Read the json from a snapshot directory instead of GitHub
+    #[arg(long, global = true, value_name = "DIR")]
+    Offline: Option<PathBuf>,
+
+    /// Directory of the http cache
+    #[arg(long, global = true, value_name = "DIR", default_value = DEFAULT_CACHE_DIR)]
+    CacheDir: PathBuf,
+
+    /// Always download, without the http cache
+    #[arg(long, global = true)]
+    NoCache: bool,
+
+    /// Read items.txt/npc_heroes.txt from a game files directory
+    #[arg(long, global = true, value_name = "DIR")]
+    Kv: Option<PathBuf>,
+
+    /// Item data fixes, overrides.toml when it is there and the built in ones otherwise
+    #[arg(long, global = true, value_name = "FILE")]
+    Overrides: Option<PathBuf>,
 }
 
-#[derive(Debug)]
-#[derive(Clone)]
-struct Hero
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Sheet
 {
-    Name: String,
-    PrimaryAttribute: String,
-    AttackType: String,
-    BAT: f32,
-    BaseAttackSpeed: i32
+    Items,
+    Heroes,
+    BuildTree,
+    NeutralItems,
+    HeroLevels,
+    HeroTimings,
 }
 
-#[derive(Debug)]
-#[derive(Clone)]
-struct Item
+const DEFAULT_SHEETS: [Sheet; 6] = [Sheet::Items, Sheet::Heroes, Sheet::BuildTree, Sheet::NeutralItems, Sheet::HeroLevels, Sheet::HeroTimings];
+
+// Which items to keep
+#[derive(Args, Clone, Copy)]
+struct ItemFilterArgs
 {
-    Name: String,
-    Damage: i32,
-    Damage_Melee: i32,
-    Damage_Ranged: i32,
-    AttackSpeed: i32,
-    Str: i32,
-    Agi: i32,
-    Int: i32,
-    ArmorCorruption: i32,
-    MagicDamage: i32,
-    MagicChance_Melee: f32,
-    MagicChance_Ranged: f32,
-    CritMultiplier: f32,
-    CritChance: f32,
-    Cost: i32,
-    IsNeutralItem: bool,
-    IsUselessItem: bool,
-}
+    /// Also keep items without any stat we model
+    #[arg(long)]
+    IncludeUseless: bool,
 
-impl Default for Item {
-    fn default() -> Item {
-        Item {
-            Name: "Unset".to_string(),
-            Damage: 0,
-            Damage_Melee: 0,
-            Damage_Ranged: 0,
-            AttackSpeed: 0,
-            ArmorCorruption: 0,
-            MagicDamage: 0,
-            MagicChance_Melee: 0.0,
-            MagicChance_Ranged: 0.0,
-            CritMultiplier: 1.0,
-            CritChance: 0.0,
-            Str: 0,
-            Agi: 0,
-            Int: 0,
-            Cost: 0,
-            IsNeutralItem: false,
-            IsUselessItem: true,
-        }
-    }
+    /// Leave out neutral items
+    #[arg(long)]
+    ExcludeNeutral: bool,
 }
 
-fn main() {
-
-    // Item list
-    let ItemDataJson = GetItemDataJsonString();
-    let mut Items: Vec<Item> = Vec::new();
+#[derive(Subcommand)]
+enum CliCommand
+{
+    /// Download the data files and report whether each one changed
+    Fetch
+    {
+        /// Also copy the files into DIR, to be used with --offline later
+        #[arg(long, value_name = "DIR")]
+        Snapshot: Option<PathBuf>,
+    },
+    /// Write the spreadsheet
+    Export(ExportArgs),
+    /// Open the spreadsheets
+    Open
+    {
+        /// Spreadsheet to open next to Dota2BuyDps.xlsm
+        #[arg(short, long, default_value = OUTPUT_FILE)]
+        Output: String,
+
+        /// Open with LibreOffice instead of the default program
+        #[arg(long = "libreoffice")]
+        LibreOffice: bool,
+    },
+    /// Print the damage per second of a hero with some items
+    Dps
+    {
+        /// Hero name, e.g. "Anti-Mage"
+        #[arg(long)]
+        Hero: String,
+
+        /// Damage of the hero without items, its level 1 damage from the hero data when left out
+        #[arg(long)]
+        BaseDamage: Option<f32>,
+
+        /// Item name, can be given several times, e.g. --item "Daedalus" --item "Dagon (Level 3)"
+        #[arg(long = "item", value_name = "ITEM")]
+        Items: Vec<String>,
+
+        /// Number of enemies standing together, for cleave and chain lightning
+        #[arg(long, default_value_t = 1)]
+        Targets: i32,
+
+        /// Print the damage per second the bought items add per 1000 gold instead, neutral items left out
+        #[arg(long)]
+        PerGold: bool,
+    },
+    /// Print the item changes between a snapshot and the current data
+    Diff
+    {
+        /// Snapshot directory with the old data
+        Old: PathBuf,
 
-    GetItemStats(&ItemDataJson, &mut Items, true);
+        /// Snapshot directory with the new data, the current source when left out
+        New: Option<PathBuf>,
 
-    // Replace item_names with real names
-    GetRealItemNames(&mut Items);
+        #[command(flatten)]
+        Filter: ItemFilterArgs,
+    },
+}
 
-    let mut wb = Workbook::create("Dota2Data.xlsx");
-    WriteItemsToXlsx(&mut wb, &Items);
+#[derive(Args)]
+struct ExportArgs
+{
+    /// Spreadsheet to write
+    #[arg(short, long, default_value = OUTPUT_FILE)]
+    Output: String,
 
+    /// Sheets to write
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = DEFAULT_SHEETS)]
+    Sheets: Vec<Sheet>,
 
+    #[command(flatten)]
+    Filter: ItemFilterArgs,
 
+    /// Do not open the spreadsheets afterwards
+    #[arg(long)]
+    NoOpen: bool,
 
-    // Hero list
-    let HeroDataJson = GetHeroDataJsonString();
-    let mut HeroList: Vec<Hero> = Vec::new();
+    /// Open with LibreOffice instead of the default program
+    #[arg(long = "libreoffice")]
+    LibreOffice: bool,
+}
 
-    GetHeroesData(&HeroDataJson, &mut HeroList);
+impl Default for ExportArgs
+{
+    fn default() -> ExportArgs
+    {
+        ExportArgs
+        {
+            Output: OUTPUT_FILE.to_string(),
+            Sheets: DEFAULT_SHEETS.to_vec(),
+            Filter: ItemFilterArgs{IncludeUseless: false, ExcludeNeutral: false},
+            NoOpen: false,
+            LibreOffice: false,
+        }
+    }
+}
 
-    WriteHeroesToXlsx(&mut wb, &HeroList);
-    // Close
-    wb.close().expect("close excel error!");
+fn main() {
 
-    OpenExcel();
-    
+    if let Err(e) = Run(Cli::parse())
+    {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
 }
 
-fn OpenExcel()
+// Prints the items/heroes that were skipped because their data could not be read
+fn ReportSkipped(What: &str, Errors: &[Error])
 {
-    // Open the file
-    let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-                .args(["/C", "start Dota2Data.xlsx"])
-                .output()
-                .expect("failed to execute process");
-
-                Command::new("cmd")
-                .args(["/C", "start Dota2BuyDps.xlsm"])
-                .output()
-                .expect("failed to execute process");
-    } else {
-        Command::new("sh")
-                .arg("-c")
-                .arg("Dota2Data.xlsx")
-                .output()
-                .expect("failed to execute process");
-
-                Command::new("sh")
-                .arg("-c")
-                .arg("Dota2BuyDps.xlsm")
-                .output()
-                .expect("failed to execute process");
-    };
+    if !Errors.is_empty()
+    {
+        eprintln!("Skipped {} {}:", Errors.len(), What);
+        for e in Errors
+        {
+            eprintln!("  {}", e);
+        }
+    }
 }
 
-fn GetItemDataJsonString() -> String
+fn ReportWarnings(Warnings: &[String])
 {
-    // Get html source
-    let url = "https://raw.githubusercontent.com/dotabuff/d2vpkr/master/dota/scripts/npc/items.json";
-    let response = reqwest::blocking::get(url).unwrap();
-    let htmlBody = response.text().unwrap();
-    htmlBody
+    for warning in Warnings
+    {
+        eprintln!("warning: {}", warning);
+    }
 }
 
-fn GetHeroDataJsonString() -> String
+fn GetSource(Args: &SourceArgs) -> DataSource
 {
-    // Get html source
-    let url = "https://raw.githubusercontent.com/odota/dotaconstants/master/build/heroes.json";
-    let response = reqwest::blocking::get(url).unwrap();
-    let htmlBody = response.text().unwrap();
-    htmlBody
+    match &Args.Offline
+    {
+        Some(dir) => DataSource::Offline(dir.clone()),
+        None if Args.NoCache => DataSource::Online(None),
+        None => DataSource::Online(Some(Args.CacheDir.clone())),
+    }
 }
 
-fn GetRealItemNames(InOutItems: &mut Vec<Item>)
+fn GetOpener(LibreOffice: bool) -> Opener
 {
-    // Get html source
-    let url = "https://raw.githubusercontent.com/odota/dotaconstants/master/build/items.json";
-    let response = reqwest::blocking::get(url).unwrap();
-    let json = response.text().unwrap();
-
-    let allItemsDotaConstants: Map<String, Value> = serde_json::from_str(&json).unwrap();
+    if LibreOffice { Opener::LibreOffice } else { Opener::Default }
+}
 
-    for item in InOutItems
+fn LoadOverrides(Args: &SourceArgs) -> Result<Overrides>
+{
+    match &Args.Overrides
     {
-        // remove item_
-        let str = &item.Name[5..];
-
-        if(allItemsDotaConstants.contains_key(str))
-        {
-            let itemInfoMap: Map<String, Value> = serde_json::from_value(allItemsDotaConstants[str].clone()).unwrap();
+        Some(file) => Overrides::Load(file),
+        None if std::path::Path::new(OVERRIDES_FILE).exists() => Overrides::Load(OVERRIDES_FILE.as_ref()),
+        None => Ok(Overrides::Builtin()),
+    }
+}
 
-            if(itemInfoMap.contains_key("dname"))
-            {
-                item.Name = itemInfoMap["dname"].as_str().unwrap().to_string();
-            }
-        }
+// Reads one data file, saying on stderr whether the cached copy was used
+fn LoadDataFile(Source: &DataSource, File: &DataFile) -> Result<String>
+{
+    let (json, status) = GetDataJsonString(Source, File)?;
+    if let Some(status) = status
+    {
+        eprintln!("{}: {}", File.SnapshotPath, status);
     }
+    Ok(json)
 }
 
-fn GetHeroesData(JsonData: &String, InOutHeroes: &mut Vec<Hero>) 
+// What fetch says about a file
+fn FetchStatusText(Source: &DataSource, Status: Option<CacheStatus>) -> String
 {
-    let parsed: Map<String, Value> = serde_json::from_str(JsonData).unwrap();
-    let allHeroes = parsed.clone();
+    match (Status, Source)
+    {
+        (Some(status), _) => status.to_string(),
+        (None, DataSource::Online(None)) => "downloaded, without the cache there is nothing to compare with".to_string(),
+        (None, _) => "read from the snapshot".to_string(),
+    }
+}
 
-    // Loop through all items, if matches criterias, add it to the item list
-    for hero in &allHeroes
+fn LoadItems(Source: &DataSource, KvDir: Option<&PathBuf>, InOverrides: &Overrides, Filter: ItemFilterArgs) -> Result<Vec<Item>>
+{
+    let ItemDataJson = match KvDir
     {
-        //println!("{:#?}", hero.1);
+        Some(dir) => GetKvItemDataJsonString(dir)?,
+        None => LoadDataFile(Source, &ITEM_DATA_FILE)?,
+    };
+    let mut Items: Vec<Item> = Vec::new();
 
-        let heroMap: Map<String, Value> = serde_json::from_value(hero.1.clone()).unwrap();
+    let itemReport = GetItemStats(&ItemDataJson, InOverrides, &mut Items, false)?;
+    ReportSkipped("items", &itemReport.Skipped);
+    ReportWarnings(&itemReport.Warnings);
 
-        let aName = heroMap["localized_name"].as_str().unwrap().to_string();
-        let aPrimaryAttribute = heroMap["primary_attr"].as_str().unwrap().to_string();
-        let aAttackType = heroMap["attack_type"].as_str().unwrap().to_string();
-        let aBAT = heroMap["attack_rate"].as_f64().unwrap().to_string().parse::<f32>().unwrap();
-        let aBaseAttackSpeed = 100;
-        let mut aHero: Hero = Hero{Name: aName, PrimaryAttribute: aPrimaryAttribute, AttackType: aAttackType, BAT: aBAT, BaseAttackSpeed: aBaseAttackSpeed};
+    // Replace item_names with real names
+    let ItemNamesJson = LoadDataFile(Source, &ITEM_NAMES_FILE)?;
+    GetRealItemNames(&ItemNamesJson, &mut Items)?;
 
-        InOutHeroes.push(aHero);
-    }
+    Ok(FilterItems(Items, Filter))
 }
 
-fn GetItemStats(JsonData: &String, InOutItems: &mut Vec<Item>, IgnoreUselessItems: bool) 
+fn FilterItems(mut Items: Vec<Item>, Filter: ItemFilterArgs) -> Vec<Item>
 {
-    let parsed: Map<String, Value> = serde_json::from_str(JsonData).unwrap();
-    let mut versionIncluded: Map<String, Value> = serde_json::from_str(&parsed["DOTAAbilities"].to_string()).unwrap();
-    versionIncluded.remove("Version");
-    let allItems = versionIncluded.clone();
+    Items.retain(|item| (Filter.IncludeUseless || !item.IsUselessItem) && !(Filter.ExcludeNeutral && item.IsNeutralItem));
+    Items
+}
 
-    // Loop through all items, if matches criterias, add it to the item list
-    for item in &allItems
+fn LoadHeroes(Source: &DataSource, KvDir: Option<&PathBuf>) -> Result<Vec<Hero>>
+{
+    let HeroDataJson = match KvDir
     {
-        //println!("{:#?}", item.0);
+        Some(dir) => GetKvHeroDataJsonString(dir)?,
+        None => LoadDataFile(Source, &HERO_DATA_FILE)?,
+    };
+    let mut HeroList: Vec<Hero> = Vec::new();
+
+    let heroReport = GetHeroesData(&HeroDataJson, &mut HeroList)?;
+    ReportSkipped("heroes", &heroReport.Skipped);
+    ReportWarnings(&heroReport.Warnings);
 
-        let mut isUselessItem = true;
+    Ok(HeroList)
+}
 
-        let aName = item.0.clone();
-        let mut aItem: Item = Item{Name: aName, ..Default::default()};
-        
-        let itemMap: Map<String, Value> = serde_json::from_value(item.1.clone()).unwrap();
+fn Run(InCli: Cli) -> Result<()>
+{
+    let source = GetSource(&InCli.Source);
+    let kvDir = InCli.Source.Kv.as_ref();
+    // Loaded up front so a broken overrides file is reported before anything is downloaded
+    let overrides = LoadOverrides(&InCli.Source)?;
 
-        // Early Continue
-        if itemMap.contains_key("IsObsolete")
+    match InCli.Command.unwrap_or(CliCommand::Export(ExportArgs::default()))
+    {
+        CliCommand::Fetch { Snapshot } =>
         {
-            if itemMap["IsObsolete"].as_str().unwrap().parse::<i32>().unwrap() > 0
+            match Snapshot
             {
-                continue;
+                Some(dir) =>
+                {
+                    for (file, status, path) in SaveSnapshot(&source, &dir)?
+                    {
+                        println!("{}: {}, saved {}", file.SnapshotPath, FetchStatusText(&source, status), path.display());
+                    }
+                }
+                None =>
+                {
+                    for file in DATA_FILES
+                    {
+                        let (_, status) = GetDataJsonString(&source, file)?;
+                        println!("{}: {}", file.SnapshotPath, FetchStatusText(&source, status));
+                    }
+                }
             }
+            Ok(())
         }
-
-        // Fill all values from json
-
-        // Cost
-        if (itemMap.contains_key("ItemCost"))
+        CliCommand::Export(args) => Export(&source, kvDir, &overrides, &args),
+        CliCommand::Open { Output, LibreOffice } =>
         {
-            // Special case
-            if(itemMap["ItemCost"].as_str().unwrap().to_string() == "")
-            {
-                aItem.Cost = 0;
-            }
-            else
-            {
-                aItem.Cost = itemMap["ItemCost"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-            }
+            ReportWarnings(&OpenExcel(&Output, GetOpener(LibreOffice))?);
+            Ok(())
         }
-
-        // IsNeutralItem
-        if (itemMap.contains_key("ItemIsNeutralDrop"))
+        CliCommand::Dps { Hero, BaseDamage, Items, Targets, PerGold } =>
         {
-            // Special case
-            if(itemMap["ItemIsNeutralDrop"].as_str().unwrap().to_string() == "")
+            let heroes = LoadHeroes(&source, kvDir)?;
+            let allItems = LoadItems(&source, kvDir, &overrides, ItemFilterArgs{IncludeUseless: true, ExcludeNeutral: false})?;
+
+            let hero = heroes.iter().find(|h| h.Name.eq_ignore_ascii_case(&Hero))
+                .ok_or_else(|| Error::Usage(format!("unknown hero: {}", Hero)))?;
+
+            let mut build: Vec<Item> = Vec::new();
+            for name in &Items
             {
-                aItem.IsNeutralItem = false;
+                let item = allItems.iter().find(|item| GetItemDisplayName(item).eq_ignore_ascii_case(name))
+                    .ok_or_else(|| Error::Usage(format!("unknown item: {}", name)))?;
+                build.push(item.clone());
             }
-            else if (itemMap["ItemIsNeutralDrop"].as_str().unwrap().to_string() == "0")
+
+            let baseDamage = BaseDamage.unwrap_or_else(|| HeroBaseDamage(hero));
+            if PerGold
             {
-                aItem.IsNeutralItem = false;
+                let perGold = DpsPerThousandGold(hero, baseDamage, &build, Targets)
+                    .ok_or_else(|| Error::Usage("none of the items costs gold".to_string()))?;
+                println!("{:.1}", perGold);
             }
-            else if (itemMap["ItemIsNeutralDrop"].as_str().unwrap().to_string() == "1")
+            else
             {
-                aItem.IsNeutralItem = true;
+                println!("{:.1}", DpsAgainst(hero, baseDamage, &build, Targets));
             }
+            Ok(())
         }
-
-        if (itemMap.contains_key("AbilitySpecial"))
+        CliCommand::Diff { Old, New, Filter } =>
         {
-            let bonusMap: Vec<Map<String,Value>> = serde_json::from_value(itemMap["AbilitySpecial"].clone()).unwrap();
-
-            for attribute in &bonusMap[..]
+            let oldItems = LoadItems(&DataSource::Offline(Old), None, &overrides, Filter)?;
+            let newItems = match New
             {
-                // Str
-                if (attribute.contains_key("bonus_strength"))
-                {
-                    if(attribute["bonus_strength"].is_number())
-                    {
-                        aItem.Str = attribute["bonus_strength"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bonus_strength"].is_string())
-                    {
-                        aItem.Str = attribute["bonus_strength"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
+                Some(dir) => LoadItems(&DataSource::Offline(dir), None, &overrides, Filter)?,
+                None => LoadItems(&source, kvDir, &overrides, Filter)?,
+            };
 
-                // Agi
-                else if (attribute.contains_key("bonus_agility"))
-                {
-                    if(attribute["bonus_agility"].is_number())
-                    {
-                        aItem.Agi = attribute["bonus_agility"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bonus_agility"].is_string())
-                    {
-                        aItem.Agi = attribute["bonus_agility"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
-
-                // Int
-                else if (attribute.contains_key("bonus_intellect"))
-                {
-                    if(attribute["bonus_intellect"].is_number())
-                    {
-                        aItem.Int = attribute["bonus_intellect"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bonus_intellect"].is_string())
-                    {
-                        aItem.Int = attribute["bonus_intellect"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
-
-                // Str,Agi,Int
-                else if (attribute.contains_key("bonus_all_stats"))
-                {
-                    if(attribute["bonus_all_stats"].is_number())
-                    {
-                        if(attribute["bonus_all_stats"].as_i64().unwrap() as i32 != 0)
-                        {
-                            aItem.Str += attribute["bonus_all_stats"].as_i64().unwrap() as i32;
-                            aItem.Agi += attribute["bonus_all_stats"].as_i64().unwrap() as i32;
-                            aItem.Int += attribute["bonus_all_stats"].as_i64().unwrap() as i32;
-                            isUselessItem = false;
-                        }
-                    }
-                    else if(attribute["bonus_all_stats"].is_string())
-                    {
-                        if(attribute["bonus_all_stats"].as_str().unwrap().to_string().parse::<i32>().unwrap() != 0)
-                        {
-                            aItem.Str += attribute["bonus_all_stats"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                            aItem.Agi += attribute["bonus_all_stats"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                            aItem.Int += attribute["bonus_all_stats"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                            isUselessItem = false;
-                        }
-                    }
-                }
-
-                // Damage
-                else if (attribute.contains_key("bonus_damage"))
-                {
-                    // Enchanted quiver damage is on cd
-                    if(item.0 != "item_enchanted_quiver")
-                    {
-                        if(attribute["bonus_damage"].is_number())
-                        {
-                            if(attribute["bonus_damage"].is_f64())
-                            {
-                                aItem.Damage = attribute["bonus_damage"].as_f64().unwrap() as i32;
-                                isUselessItem = false;
-                            }
-                            else
-                            {
-                                aItem.Damage = attribute["bonus_damage"].as_i64().unwrap() as i32;
-                                isUselessItem = false;
-                            }
-                        }
-                        else if(attribute["bonus_damage"].is_string())
-                        {
-                            aItem.Damage = attribute["bonus_damage"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                            isUselessItem = false;
-                        }
-                    }
-                }
-
-                // Damage Melee
-                else if (attribute.contains_key("bonus_damage_melee"))
-                {
-                    if(attribute["bonus_damage_melee"].is_number())
-                    {
-                        aItem.Damage_Melee = attribute["bonus_damage_melee"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bonus_damage_melee"].is_string())
-                    {
-                        aItem.Damage_Melee = attribute["bonus_damage_melee"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
-
-                // Damage Ranged
-                else if (attribute.contains_key("bonus_damage_range"))
-                {
-                    if(attribute["bonus_damage_range"].is_number())
-                    {
-                        aItem.Damage_Ranged = attribute["bonus_damage_range"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bonus_damage_range"].is_string())
-                    {
-                        aItem.Damage_Ranged = attribute["bonus_damage_range"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
-
-                // Attack Speed
-                else if (attribute.contains_key("bonus_attack_speed"))
-                {
-                    // Hurricanes attack speed is activate
-                    if(item.0 != "item_hurricane_pike")
-                    {
-                        if(attribute["bonus_attack_speed"].is_number())
-                        {
-                            aItem.AttackSpeed = attribute["bonus_attack_speed"].as_i64().unwrap() as i32;
-                            isUselessItem = false;
-                        }
-                        else if(attribute["bonus_attack_speed"].is_string())
-                        {
-                            aItem.AttackSpeed = attribute["bonus_attack_speed"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                            isUselessItem = false;
-                        }
-                    }
-                }
-
-                // Armor Corr
-                else if (attribute.contains_key("corruption_armor"))
-                {
-                    if(attribute["corruption_armor"].is_number())
-                    {
-                        aItem.ArmorCorruption = attribute["corruption_armor"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["corruption_armor"].is_string())
-                    {
-                        aItem.ArmorCorruption = attribute["corruption_armor"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
-
-                // For some reason source json has "armor" as corruption for item_orb_of_corrosion
-                else if (attribute.contains_key("armor"))
-                {
-                    if(item.0 == "item_orb_of_corrosion")
-                    {
-                        if(attribute["armor"].is_number())
-                        {
-                            aItem.ArmorCorruption = -attribute["armor"].as_i64().unwrap() as i32;
-                            isUselessItem = false;
-                        }
-                        else if(attribute["armor"].is_string())
-                        {
-                            aItem.ArmorCorruption = -attribute["armor"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                            isUselessItem = false;
-                        }
-                    }
-                }
-
-                // Magic Damage
-                else if (attribute.contains_key("chain_damage"))
-                {
-                    if(attribute["chain_damage"].is_number())
-                    {
-                        aItem.MagicDamage = attribute["chain_damage"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["chain_damage"].is_string())
-                    {
-                        aItem.MagicDamage = attribute["chain_damage"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
-
-                // Magic Damage (& bonus_chance_damage) since their calculation is the same
-                else if (attribute.contains_key("bonus_chance_damage"))
-                {
-                    if(attribute["bonus_chance_damage"].is_number())
-                    {
-                        aItem.MagicDamage = attribute["bonus_chance_damage"].as_i64().unwrap() as i32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bonus_chance_damage"].is_string())
-                    {
-                        aItem.MagicDamage = attribute["bonus_chance_damage"].as_str().unwrap().to_string().parse::<i32>().unwrap();
-                        isUselessItem = false;
-                    }
-                }
-
-                // Magic %
-                else if (attribute.contains_key("chain_chance"))
-                {
-                    if(attribute["chain_chance"].is_number())
-                    {
-                        aItem.MagicChance_Melee = attribute["chain_chance"].as_i64().unwrap() as f32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["chain_chance"].is_string())
-                    {
-                        aItem.MagicChance_Melee = attribute["chain_chance"].as_str().unwrap().to_string().parse::<f32>().unwrap();
-                        isUselessItem = false;
-                    }
-                    aItem.MagicChance_Melee = aItem.MagicChance_Melee / 100.0;
-                    aItem.MagicChance_Ranged = aItem.MagicChance_Melee;
-                }
-
-                // Magic % (&bonus_chance)
-                else if (attribute.contains_key("bonus_chance"))
-                {
-                    if(attribute["bonus_chance"].is_number())
-                    {
-                        aItem.MagicChance_Melee = attribute["bonus_chance"].as_i64().unwrap() as f32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bonus_chance"].is_string())
-                    {
-                        aItem.MagicChance_Melee = attribute["bonus_chance"].as_str().unwrap().to_string().parse::<f32>().unwrap();
-                        isUselessItem = false;
-                    }
-                    aItem.MagicChance_Melee = aItem.MagicChance_Melee / 100.0;
-                    aItem.MagicChance_Ranged = aItem.MagicChance_Melee;
-                }
-
-                // Magic % Bash Melee
-                else if (attribute.contains_key("bash_chance_melee"))
-                {
-                    if(attribute["bash_chance_melee"].is_number())
-                    {
-                        aItem.MagicChance_Melee = attribute["bash_chance_melee"].as_f64().unwrap() as f32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bash_chance_melee"].is_string())
-                    {
-                        aItem.MagicChance_Melee = attribute["bash_chance_melee"].as_str().unwrap().to_string().parse::<f32>().unwrap();
-                        isUselessItem = false;
-                    }
-                    aItem.MagicChance_Melee = aItem.MagicChance_Melee / 100.0;
-                }
-
-                // Magic % Bash Ranged
-                else if (attribute.contains_key("bash_chance_ranged"))
-                {
-                    if(attribute["bash_chance_ranged"].is_number())
-                    {
-                        aItem.MagicChance_Ranged = attribute["bash_chance_ranged"].as_f64().unwrap() as f32;
-                        isUselessItem = false;
-                    }
-                    else if(attribute["bash_chance_ranged"].is_string())
-                    {
-                        aItem.MagicChance_Ranged = attribute["bash_chance_ranged"].as_str().unwrap().to_string().parse::<f32>().unwrap();
-                        isUselessItem = false;
-                    }
-                    aItem.MagicChance_Ranged = aItem.MagicChance_Ranged / 100.0;
-                }
-
-                // Crit Multiplier
-                else if (attribute.contains_key("crit_multiplier"))
-                {
-                    // bloodthorn crit is activate
-                    if(item.0 != "item_bloodthorn")
-                    {
-                        if(attribute["crit_multiplier"].is_number())
-                        {
-                            aItem.CritMultiplier = attribute["crit_multiplier"].as_i64().unwrap() as f32;
-                            isUselessItem = false;
-                        }
-                        else if(attribute["crit_multiplier"].is_string())
-                        {
-                            aItem.CritMultiplier = attribute["crit_multiplier"].as_str().unwrap().to_string().parse::<f32>().unwrap();
-                            isUselessItem = false;
-                        }
-                        aItem.CritMultiplier = aItem.CritMultiplier / 100.0;
-                    }
-                }
-
-                // Crit %
-                else if (attribute.contains_key("crit_chance"))
+            for change in DiffItems(&oldItems, &newItems)
+            {
+                match change
                 {
-                    // bloodthorn crit is activate
-                    if(item.0 != "item_bloodthorn")
+                    ItemChange::Added(name) => println!("+ {}", name),
+                    ItemChange::Removed(name) => println!("- {}", name),
+                    ItemChange::Changed(name, columns) =>
                     {
-                        if(attribute["crit_chance"].is_number())
-                        {
-                            aItem.CritChance = attribute["crit_chance"].as_f64().unwrap() as f32;
-                            isUselessItem = false;
-                        }
-                        else if(attribute["crit_chance"].is_string())
+                        println!("~ {}", name);
+                        for (column, oldValue, newValue) in columns
                         {
-                            aItem.CritChance = attribute["crit_chance"].as_str().unwrap().to_string().parse::<f32>().unwrap();
-                            isUselessItem = false;
+                            println!("    {}: {} -> {}", column, oldValue, newValue);
                         }
-                        aItem.CritChance = aItem.CritChance / 100.0;
                     }
                 }
             }
+            Ok(())
         }
-
-        // Set IsUselessItem
-        aItem.IsUselessItem = isUselessItem;
-
-        // If ignore useless items
-        if(IgnoreUselessItems && isUselessItem)
-        {
-            continue;
-        }
-
-        // Add to list
-        InOutItems.push(aItem);
     }
-    
 }
 
-fn GetUrlForItem(ItemName: &String) -> String
+fn Export(Source: &DataSource, KvDir: Option<&PathBuf>, InOverrides: &Overrides, Args: &ExportArgs) -> Result<()>
 {
-    let mut commonUrl: String = String::from("https://dota2.fandom.com/wiki/");
-    let mut specialUrl = ItemName.clone().replace(' ', "_");
-    let newUrl = commonUrl + &specialUrl;
+    let mut wb = Workbook::create_in_memory();
 
-    newUrl
-}
-
-fn GetItemNames(ItemList: &mut Vec<ItemEntry>)
-{
-    // Get html source
-    let response = reqwest::blocking::get("https://dota2.fandom.com/wiki/Items").unwrap();
-    let htmlBody = response.text().unwrap();
-    let text = &htmlBody[..];
-
-    let fragment = Html::parse_fragment(text);
-    let selectorItemList = Selector::parse("div.itemlist").unwrap();
-    let selectorDiv = Selector::parse("div").unwrap();
-    let selectorA = Selector::parse("a").unwrap();
-
-    // Find all items by looping all class="itemlist"
-    let mut categoryCounter = 0;
-    for itemlistElement in fragment.select(&selectorItemList) {
-        let mut category = "-1337";
-        match categoryCounter {
-            0 | 1 | 2 | 3 | 4 => 
-            category = "Basics Items",
-            5 | 6 | 7 | 8 | 9 | 10 => 
-            category = "Upgraded Items",
-            11 | 12 | 13 | 14 | 15 | 16 | 17 => 
-            category = "Neutral Items",
-            18 => 
-            category = "Roshan Drop",
-            19 => 
-            category = "Unreleased Items",
-            20 | 21 => 
-            category = "Removed Items",
-            22 | 23 | 24 | 25 | 26 | 27 | 28 => 
-            category = "Event Items",
-            _ => category = "No idea",
-        }
-
-        for element in itemlistElement.select(&selectorDiv) {
-            let mut elementList = element.select(&selectorA);
-            // Second a
-            let mut e = elementList.next();
-            e = elementList.next();
-
-            let itemName = e.unwrap().inner_html();
-            let itemEntry = ItemEntry {Name: itemName, Category: category.to_string()};
-            ItemList.push(itemEntry)
-        }
-
-        categoryCounter = categoryCounter + 1;
+    // Build trees need the components without stats too
+    let needsItems = [Sheet::Items, Sheet::BuildTree, Sheet::NeutralItems].iter().any(|sheet| Args.Sheets.contains(sheet));
+    let allItems = if needsItems
+    {
+        LoadItems(Source, KvDir, InOverrides, ItemFilterArgs{IncludeUseless: true, ExcludeNeutral: false})?
     }
-}
-
-fn WriteItemsToXlsx(wb: &mut Workbook, InItems: &Vec<Item>)
-{
-    // Create Sheet
-    let mut sheet = wb.create_sheet("Items");
-
-    // Write to Sheet
-    wb.write_sheet(&mut sheet, |sheet_writer| {
-        let sw = sheet_writer;
-        
-        for item in InItems
-        {
-            WriteItem(sw, &item);
-        }
-        
-        Ok(())
-    }).unwrap();
-}
-
-fn WriteItem(sw: &mut SheetWriter, InItem: &Item)
-{
-    sw.append_row(row![
-        InItem.Name.clone(),
-        InItem.Cost.to_string(),
-        InItem.Damage.to_string(),
-        InItem.Damage_Melee.to_string(),
-        InItem.Damage_Ranged.to_string(),
-        InItem.AttackSpeed.to_string(),
-        InItem.Str.to_string(),
-        InItem.Agi.to_string(),
-        InItem.Int.to_string(),
-        InItem.ArmorCorruption.to_string(),
-        InItem.MagicDamage.to_string(),
-        InItem.MagicChance_Melee.to_string(),
-        InItem.MagicChance_Ranged.to_string(),
-        InItem.CritMultiplier.to_string(),
-        InItem.CritChance.to_string(),
-        InItem.IsNeutralItem.to_string()
-    ]);
-}
-
-fn WriteHeroesToXlsx(wb: &mut Workbook, InHeroes: &Vec<Hero>)
-{
-    // Create Sheet
-    let mut sheet = wb.create_sheet("Heroes");
-
-    // Write to Sheet
-    wb.write_sheet(&mut sheet, |sheet_writer| {
-        let sw = sheet_writer;
-        
-        for hero in InHeroes
-        {
-            sw.append_row(row![
-                hero.Name.clone(),
-                hero.PrimaryAttribute.to_string(),
-                hero.AttackType.to_string(),
-                hero.BAT.to_string(),
-                hero.BaseAttackSpeed.to_string()
-            ]);
-        }
-
-        Ok(())
-    }).unwrap();
-}
-
-/*
-fn GetItemStats(JsonData: &String, InOutItem: &mut Item) 
-{
-    let itemURL = GetUrlForItem(&InOutItem.Name);
-
-    // Get html source
-    let response = reqwest::blocking::get(itemURL).unwrap();
-    let htmlBody = response.text().unwrap();
-    let text = &htmlBody[..];
-
-    let fragment = Html::parse_fragment(text);
-    let selectorInfoBox = Selector::parse("table.infobox").unwrap();
-    let selectorTr = Selector::parse("tr").unwrap();
-    let selectorDivDiv = Selector::parse("div").unwrap();
-
-    // Find stats (infobox)
-    let InfoBox = fragment.select(&selectorInfoBox).next().unwrap();
-
-    // Find cost
-    let mut costTrIt = InfoBox.select(&selectorTr);
-    
-    // Get fourth tr
-    let mut costTr = costTrIt.next();
-    costTr = costTrIt.next();
-    costTr = costTrIt.next();
-    costTr = costTrIt.next();
-
-    let mut divdivIt = costTr.unwrap().select(&selectorDivDiv);
+    else
+    {
+        Vec::new()
+    };
 
-    // Second div is the cost
-    let mut costDiv = divdivIt.next();
-    costDiv = divdivIt.next();
-    let costString = costDiv.unwrap().inner_html();
+    // Items and Heroes stay the first two sheets for Dota2BuyDps.xlsm
+    if Args.Sheets.contains(&Sheet::Items)
+    {
+        WriteItemsToXlsx(&mut wb, &FilterItems(allItems.clone(), Args.Filter))?;
+    }
 
-    
+    let HeroList = if Args.Sheets.contains(&Sheet::Heroes) || Args.Sheets.contains(&Sheet::HeroLevels) || Args.Sheets.contains(&Sheet::HeroTimings)
+    {
+        LoadHeroes(Source, KvDir)?
+    }
+    else
+    {
+        Vec::new()
+    };
 
-    // Get the cost as int
-    let searchStr = "Cost<br>";
-    let searchStrLen = searchStr.chars().count();
-    let costIndex = costString.find(searchStr).unwrap();
+    if Args.Sheets.contains(&Sheet::Heroes)
+    {
+        WriteHeroesToXlsx(&mut wb, &HeroList)?;
+    }
 
-    // Find cost xxxx end
-    let searchStr2 = " ";
-    let costEndIndex = costString.find(searchStr2).unwrap();
+    if Args.Sheets.contains(&Sheet::BuildTree)
+    {
+        WriteBuildTreeToXlsx(&mut wb, &allItems)?;
+    }
 
-    let str = &costString[costIndex+searchStrLen..costEndIndex].to_string();
-    let costAsInt = str.parse::<i32>().unwrap();
+    if Args.Sheets.contains(&Sheet::NeutralItems)
+    {
+        WriteNeutralItemsToXlsx(&mut wb, &allItems)?;
+    }
 
-    // Find stats (bonus)
-    let selectorInt = Selector::parse("div").unwrap();
+    if Args.Sheets.contains(&Sheet::HeroLevels)
+    {
+        WriteHeroLevelsToXlsx(&mut wb, &HeroList)?;
+    }
 
-    //let bonus = 
+    if Args.Sheets.contains(&Sheet::HeroTimings)
+    {
+        WriteHeroTimingsToXlsx(&mut wb, &HeroList)?;
+    }
 
+    // Close
+    CloseWorkbook(&mut wb, &Args.Output)?;
 
-    println!("{:#?}", costAsInt);
-}*/
\ No newline at end of file
+    if Args.NoOpen
+    {
+        return Ok(());
+    }
+    ReportWarnings(&OpenExcel(&Args.Output, GetOpener(Args.LibreOffice))?);
+    Ok(())
+}
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+
+// Minute each neutral item tier starts dropping, tier 1 first
+pub const NEUTRAL_TIER_MINUTES: [i32; 5] = [5, 15, 25, 35, 60];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimaryAttribute
+{
+    // The game's default, like npc_dota_hero_base
+    #[default]
+    Strength,
+    Agility,
+    Intelligence,
+    // Damage from all three attributes
+    Universal,
+}
+
+impl PrimaryAttribute
+{
+    // From the dotaconstants "primary_attr" value, None for values we do not know
+    pub fn FromDataName(Name: &str) -> Option<PrimaryAttribute>
+    {
+        match Name
+        {
+            "str" => Some(PrimaryAttribute::Strength),
+            "agi" => Some(PrimaryAttribute::Agility),
+            "int" => Some(PrimaryAttribute::Intelligence),
+            "all" => Some(PrimaryAttribute::Universal),
+            _ => None,
+        }
+    }
+
+    // The dotaconstants name, which is also what the Heroes sheet has
+    pub fn DataName(&self) -> &'static str
+    {
+        match self
+        {
+            PrimaryAttribute::Strength => "str",
+            PrimaryAttribute::Agility => "agi",
+            PrimaryAttribute::Intelligence => "int",
+            PrimaryAttribute::Universal => "all",
+        }
+    }
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Hero
+{
+    pub Name: String,
+    pub PrimaryAttribute: PrimaryAttribute,
+    pub AttackType: String,
+    pub BAT: f32,
+    pub BaseAttackSpeed: i32,
+    // Attributes at level 1 and what each level adds
+    pub BaseStr: f32,
+    pub BaseAgi: f32,
+    pub BaseInt: f32,
+    pub StrGain: f32,
+    pub AgiGain: f32,
+    pub IntGain: f32,
+    // Attack damage without attributes, a hit deals a random value between the two
+    pub BaseAttackMin: i32,
+    pub BaseAttackMax: i32,
+    pub BaseArmor: f32,
+    // 0-1
+    pub BaseMagicResist: f32,
+    pub AttackRange: i32,
+    pub ProjectileSpeed: i32,
+    // Seconds of the attack animation at 100 attack speed before the attack goes off.
+    // There is no backswing, neither data source has it.
+    pub AttackPoint: f32,
+    pub MoveSpeed: i32,
+    pub TurnRate: f32,
+    // Without attributes
+    pub BaseHealth: f32,
+    pub BaseHealthRegen: f32,
+    pub BaseMana: f32,
+    pub BaseManaRegen: f32,
+}
+
+// What an item does to the target of each attack
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OnHitEffects
+{
+    // Battle Fury, fraction of the attack dealt to enemies within CleaveRadius. Melee only.
+    pub CleavePercent: f32,
+    pub CleaveRadius: f32,
+    // Maelstrom/Mjollnir/Gleipnir chain lightning, each with its own values
+    pub ChainChance: f32,
+    pub ChainDamage: f32,
+    // Enemies hit by one chain lightning, the attacked one included
+    pub ChainStrikes: i32,
+    // Skull Basher/Abyssal Blade
+    pub BashChance_Melee: f32,
+    pub BashChance_Ranged: f32,
+    pub BashDamage: f32,
+    pub BashStun: f32,
+    // Monkey King Bar/Javelin, the proc can not miss
+    pub TrueStrikeChance: f32,
+    pub PierceDamage: f32,
+    // Eye of Skadi, move slow as 0-1
+    pub SlowMoveSpeed_Melee: f32,
+    pub SlowMoveSpeed_Ranged: f32,
+    pub SlowAttackSpeed_Melee: i32,
+    pub SlowAttackSpeed_Ranged: i32,
+    pub SlowDuration: f32,
+    // Desolator/Orb of Corrosion, how long ArmorCorruption lasts
+    pub CorruptionDuration: f32,
+}
+
+// The active or passive ability of an item, with the values that only apply while it is used
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemAbility
+{
+    pub IsPassive: bool,
+    // Seconds
+    pub Cooldown: f32,
+    pub ManaCost: f32,
+    pub Duration: f32,
+    // e.g. Bloodthorn's "crit_multiplier", which is not a permanent stat
+    pub Specials: BTreeMap<String, f32>,
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct Item
+{
+    pub Name: String,
+    // Internal name, e.g. "item_lesser_crit". Name becomes the display name.
+    pub Key: String,
+    pub Damage: i32,
+    pub Damage_Melee: i32,
+    pub Damage_Ranged: i32,
+    pub AttackSpeed: i32,
+    pub Str: i32,
+    pub Agi: i32,
+    pub Int: i32,
+    pub ArmorCorruption: i32,
+    pub MagicDamage: i32,
+    pub MagicChance_Melee: f32,
+    pub MagicChance_Ranged: f32,
+    pub CritMultiplier: f32,
+    pub CritChance: f32,
+    pub Armor: f32,
+    // Percentages as 0-1 like CritChance
+    pub Evasion: f32,
+    pub MagicResistance: f32,
+    pub StatusResistance: f32,
+    pub SlowResistance: f32,
+    pub Health: i32,
+    pub Mana: i32,
+    // Per second
+    pub HealthRegen: f32,
+    pub ManaRegen: f32,
+    // Fraction of the damage dealt that heals, 0-1
+    pub Lifesteal: f32,
+    pub SpellLifesteal: f32,
+    pub MoveSpeed: i32,
+    pub MoveSpeedPercent: f32,
+    pub AttackRange_Melee: i32,
+    pub AttackRange_Ranged: i32,
+    pub ProjectileSpeed: i32,
+    pub SpellAmp: f32,
+    pub CastRange: i32,
+    pub CooldownReduction: f32,
+    pub OnHit: OnHitEffects,
+    // None for items without cooldown, mana cost or ability values
+    pub Ability: Option<ItemAbility>,
+    // Keys of the items it is built from, and the cost of its recipe
+    pub Components: Vec<String>,
+    pub RecipeCost: i32,
+    pub Cost: i32,
+    pub IsNeutralItem: bool,
+    // Neutral items only: tier 1-5, the minute the tier drops and whether it still drops this patch
+    pub NeutralTier: i32,
+    pub AvailableMinute: i32,
+    pub IsInDropPool: bool,
+    pub IsConsumable: bool,
+    pub Charges: i32,
+    // Has a second, consumed item with the stats kept after using it (Moon Shard). IsConsumed is that item.
+    pub IsPermanentWhenConsumed: bool,
+    pub IsConsumed: bool,
+    pub IsUselessItem: bool,
+    // Multi level items (Dagon, Necronomicon) have one Item per level
+    pub Level: i32,
+    pub MaxLevel: i32,
+    // All levels of the values that differ per level, e.g. "bonus_intellect" -> [7, 9, 11, 13, 15]
+    pub LevelValues: BTreeMap<String, Vec<f32>>,
+}
+
+impl Default for Item {
+    fn default() -> Item {
+        Item {
+            Name: "Unset".to_string(),
+            Key: String::new(),
+            Damage: 0,
+            Damage_Melee: 0,
+            Damage_Ranged: 0,
+            AttackSpeed: 0,
+            ArmorCorruption: 0,
+            MagicDamage: 0,
+            MagicChance_Melee: 0.0,
+            MagicChance_Ranged: 0.0,
+            CritMultiplier: 1.0,
+            CritChance: 0.0,
+            Armor: 0.0,
+            Evasion: 0.0,
+            MagicResistance: 0.0,
+            StatusResistance: 0.0,
+            SlowResistance: 0.0,
+            Health: 0,
+            Mana: 0,
+            HealthRegen: 0.0,
+            ManaRegen: 0.0,
+            Lifesteal: 0.0,
+            SpellLifesteal: 0.0,
+            MoveSpeed: 0,
+            MoveSpeedPercent: 0.0,
+            AttackRange_Melee: 0,
+            AttackRange_Ranged: 0,
+            ProjectileSpeed: 0,
+            SpellAmp: 0.0,
+            CastRange: 0,
+            CooldownReduction: 0.0,
+            OnHit: OnHitEffects::default(),
+            Ability: None,
+            Components: Vec::new(),
+            RecipeCost: 0,
+            Str: 0,
+            Agi: 0,
+            Int: 0,
+            Cost: 0,
+            IsNeutralItem: false,
+            NeutralTier: 0,
+            AvailableMinute: 0,
+            IsInDropPool: false,
+            IsConsumable: false,
+            Charges: 0,
+            IsPermanentWhenConsumed: false,
+            IsConsumed: false,
+            IsUselessItem: true,
+            Level: 1,
+            MaxLevel: 1,
+            LevelValues: BTreeMap::new(),
+        }
+    }
+}
+
+// e.g. "Active: 15s cooldown, 100 mana, 5s, crit_multiplier 175"
+pub fn GetAbilityDescription(InAbility: &ItemAbility) -> String
+{
+    let mut parts: Vec<String> = Vec::new();
+    if InAbility.Cooldown > 0.0
+    {
+        parts.push(format!("{}s cooldown", InAbility.Cooldown));
+    }
+    if InAbility.ManaCost > 0.0
+    {
+        parts.push(format!("{} mana", InAbility.ManaCost));
+    }
+    if InAbility.Duration > 0.0
+    {
+        parts.push(format!("{}s", InAbility.Duration));
+    }
+    for (key, value) in &InAbility.Specials
+    {
+        parts.push(format!("{} {}", key, value));
+    }
+
+    let kind = if InAbility.IsPassive { "Passive" } else { "Active" };
+    if parts.is_empty() { kind.to_string() } else { format!("{}: {}", kind, parts.join(", ")) }
+}
+
+// Name shown in the spreadsheet, multi level items get their level appended and consumed items "(Consumed)"
+pub fn GetItemDisplayName(InItem: &Item) -> String
+{
+    let name = if InItem.MaxLevel > 1
+    {
+        format!("{} (Level {})", InItem.Name, InItem.Level)
+    }
+    else
+    {
+        InItem.Name.clone()
+    };
+
+    if InItem.IsConsumed { format!("{} (Consumed)", name) } else { name }
+}
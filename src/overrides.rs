@@ -0,0 +1,225 @@
+// User editable fixes for the upstream item data, see overrides.toml
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::{Error, IoError, Result};
+
+pub const OVERRIDES_FILE: &str = "overrides.toml";
+
+// Used when there is no overrides.toml in the working directory and no --overrides file
+const BUILTIN_OVERRIDES: &str = include_str!("../overrides.toml");
+
+// What to change in one item. Fields are named like the keys in the file.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemOverride
+{
+    pub ignore: Vec<String>,
+    pub rename: BTreeMap<String, String>,
+    pub negate: Vec<String>,
+    pub replace: BTreeMap<String, f32>,
+    pub ability: Vec<String>,
+    pub consumed: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Overrides
+{
+    pub Items: BTreeMap<String, ItemOverride>,
+}
+
+impl Overrides
+{
+    // Parses and validates the text of an overrides file
+    pub fn Parse(File: &str, Text: &str) -> Result<Overrides>
+    {
+        let items: BTreeMap<String, ItemOverride> = toml::from_str(Text)
+            .map_err(|e| Error::Parse { File: File.to_string(), Message: e.message().to_string() })?;
+
+        for (itemName, itemOverride) in &items
+        {
+            ValidateItemOverride(itemName, itemOverride)
+                .map_err(|(path, message)| Error::Schema { Key: format!("{} [{}]", File, itemName), Path: path.to_string(), Message: message })?;
+        }
+
+        Ok(Overrides { Items: items })
+    }
+
+    pub fn Load(FilePath: &Path) -> Result<Overrides>
+    {
+        let text = std::fs::read_to_string(FilePath).map_err(IoError(FilePath))?;
+        Overrides::Parse(&FilePath.display().to_string(), &text)
+    }
+
+    // The overrides.toml the program was built with
+    pub fn Builtin() -> Overrides
+    {
+        Overrides::Parse(OVERRIDES_FILE, BUILTIN_OVERRIDES).expect("the built in overrides.toml is valid")
+    }
+
+    // Keys of ItemName's specials that belong to its ability
+    pub fn AbilityKeys(&self, ItemName: &str) -> &[String]
+    {
+        self.Items.get(ItemName).map_or(&[], |itemOverride| &itemOverride.ability)
+    }
+
+    // Keys of ItemName's specials kept once it is consumed, and the stat each one is read as
+    pub fn ConsumedKeys(&self, ItemName: &str) -> BTreeMap<String, String>
+    {
+        self.Items.get(ItemName).map(|itemOverride| itemOverride.consumed.clone()).unwrap_or_default()
+    }
+
+    // Applies the ignore/rename/replace/negate fixes of ItemName to its specials, in that order.
+    // Returns the keys the override names that the item does not have, e.g. after a patch renamed them.
+    pub fn Apply(&self, ItemName: &str, Specials: &mut Map<String, Value>) -> Vec<String>
+    {
+        let itemOverride = match self.Items.get(ItemName)
+        {
+            Some(itemOverride) => itemOverride,
+            None => return Vec::new(),
+        };
+        let mut missingKeys: Vec<String> = Vec::new();
+
+        for key in &itemOverride.ignore
+        {
+            if Specials.remove(key).is_none()
+            {
+                missingKeys.push(key.clone());
+            }
+        }
+        for (oldKey, newKey) in &itemOverride.rename
+        {
+            match Specials.remove(oldKey)
+            {
+                Some(value) => { Specials.insert(newKey.clone(), value); }
+                None => missingKeys.push(oldKey.clone()),
+            }
+        }
+        // replace may add a value the item does not have
+        for (key, value) in &itemOverride.replace
+        {
+            Specials.insert(key.clone(), Value::from(*value as f64));
+        }
+        for key in &itemOverride.negate
+        {
+            match Specials.get_mut(key)
+            {
+                Some(value) => *value = Negate(value),
+                None => missingKeys.push(key.clone()),
+            }
+        }
+        for key in itemOverride.ability.iter().chain(itemOverride.consumed.keys())
+        {
+            if !Specials.contains_key(key)
+            {
+                missingKeys.push(key.clone());
+            }
+        }
+
+        missingKeys.dedup();
+        missingKeys
+    }
+}
+
+// Negates a number or every value of a space separated per level value
+fn Negate(InValue: &Value) -> Value
+{
+    match InValue
+    {
+        Value::Number(n) => Value::from(-n.as_f64().unwrap_or(0.0)),
+        Value::String(text) =>
+        {
+            let negated: Vec<String> = text.split_whitespace()
+                .map(|v| v.parse::<f32>().map_or(v.to_string(), |v| (-v).to_string()))
+                .collect();
+            Value::String(negated.join(" "))
+        }
+        v => v.clone(),
+    }
+}
+
+// Returns the field and what is wrong with it
+fn ValidateItemOverride(ItemName: &str, InOverride: &ItemOverride) -> std::result::Result<(), (&'static str, String)>
+{
+    if !ItemName.starts_with("item_")
+    {
+        return Err(("", "items are named like item_blink".to_string()));
+    }
+    for (oldKey, newKey) in &InOverride.rename
+    {
+        if newKey.trim().is_empty()
+        {
+            return Err(("rename", format!("{} is renamed to nothing, use ignore instead", oldKey)));
+        }
+        if InOverride.ignore.contains(oldKey)
+        {
+            return Err(("rename", format!("{} is both ignored and renamed", oldKey)));
+        }
+    }
+    for key in InOverride.negate.iter().chain(InOverride.ability.iter()).chain(InOverride.replace.keys()).chain(InOverride.consumed.keys())
+    {
+        if InOverride.ignore.contains(key)
+        {
+            return Err(("ignore", format!("{} is ignored but also changed", key)));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn BuiltinOverridesAreValid()
+    {
+        let overrides = Overrides::Builtin();
+        assert_eq!(overrides.AbilityKeys("item_bloodthorn"), ["crit_multiplier", "crit_chance"]);
+        assert!(overrides.AbilityKeys("item_blink").is_empty());
+    }
+
+    #[test]
+    fn AppliesFixesInOrder()
+    {
+        let overrides = Overrides::Parse("test.toml", r#"
+            [item_test]
+            ignore = ["bonus_armor"]
+            rename = { armor = "corruption_armor" }
+            negate = ["corruption_armor"]
+            replace = { bonus_damage = 20 }
+        "#).unwrap();
+
+        let mut specials: Map<String, Value> = serde_json::from_str(r#"{"bonus_armor": "5", "armor": "-2 -3", "bonus_damage": "10"}"#).unwrap();
+        assert!(overrides.Apply("item_test", &mut specials).is_empty());
+        assert_eq!(Value::Object(specials), serde_json::json!({"corruption_armor": "2 3", "bonus_damage": 20.0}));
+    }
+
+    #[test]
+    fn ReportsKeysTheItemDoesNotHave()
+    {
+        let overrides = Overrides::Parse("test.toml", r#"
+            [item_test]
+            rename = { armor = "corruption_armor" }
+            negate = ["corruption_armor"]
+            ability = ["bonus_attack_speed"]
+        "#).unwrap();
+
+        // armor was renamed upstream, so nothing is renamed or negated
+        let mut specials: Map<String, Value> = serde_json::from_str(r#"{"corruption": "-2", "bonus_attack_speed": "100"}"#).unwrap();
+        assert_eq!(overrides.Apply("item_test", &mut specials), ["armor", "corruption_armor"]);
+    }
+
+    #[test]
+    fn RejectsBadOverrides()
+    {
+        let unknownField = Overrides::Parse("test.toml", "[item_test]\nremove = [\"armor\"]").unwrap_err();
+        assert!(unknownField.to_string().contains("unknown field `remove`"), "{}", unknownField);
+
+        let conflict = Overrides::Parse("test.toml", "[item_test]\nignore = [\"armor\"]\nnegate = [\"armor\"]").unwrap_err();
+        assert_eq!(conflict.to_string(), "test.toml [item_test] at ignore: armor is ignored but also changed");
+    }
+}
@@ -0,0 +1,886 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use crate::error::{Error, FromValue, ParseJson, Result};
+use crate::fetch::{HERO_DATA_FILE, ITEM_DATA_FILE, ITEM_NAMES_FILE};
+use crate::model::{Hero, Item, ItemAbility, OnHitEffects, PrimaryAttribute, NEUTRAL_TIER_MINUTES};
+use crate::overrides::Overrides;
+use crate::schema::{AbilityEntry, HeroRecord, ItemRecords, ItemSpecials};
+
+// Sets the display names, components, recipe costs and neutral tiers from dotaconstants items.json
+pub fn GetRealItemNames(JsonData: &str, InOutItems: &mut Vec<Item>) -> Result<()>
+{
+    let allItemsDotaConstants: ItemRecords = ParseJson(ITEM_NAMES_FILE.SnapshotPath, JsonData)?;
+
+    for item in InOutItems
+    {
+        // remove item_
+        let str = item.Key.strip_prefix("item_").unwrap_or(&item.Key);
+
+        let record = match allItemsDotaConstants.get(str)
+        {
+            Some(record) => record,
+            None => continue,
+        };
+
+        if let Some(dname) = record.dname.clone()
+        {
+            item.Name = dname;
+        }
+
+        // Retired neutral items keep their tier but are no longer a neutral drop in items.json
+        if let Some(tier) = record.tier.map(|tier| tier as i32).filter(|tier| (1..=5).contains(tier))
+        {
+            item.IsNeutralItem = true;
+            item.NeutralTier = tier;
+            item.AvailableMinute = NEUTRAL_TIER_MINUTES[(tier - 1) as usize];
+        }
+
+        let components = record.components.clone().unwrap_or_default();
+        if !components.is_empty()
+        {
+            // The recipe is whatever the components do not pay for
+            let componentsCost: f32 = components.iter()
+                .filter_map(|component| allItemsDotaConstants.get(component).and_then(|c| c.cost))
+                .sum();
+            let cost = record.cost.unwrap_or(item.Cost as f32);
+            item.RecipeCost = (cost - componentsCost).max(0.0) as i32;
+            item.Components = components.iter().map(|component| format!("item_{}", component)).collect();
+        }
+    }
+    Ok(())
+}
+
+// The game's base attack speed, for heroes whose data does not have one
+pub const DEFAULT_BASE_ATTACK_SPEED: i32 = 100;
+
+// What reading the data left out
+#[derive(Debug, Default)]
+pub struct ParseReport
+{
+    // Entries that could not be read and were skipped
+    pub Skipped: Vec<Error>,
+    // Entries that were read but may be wrong, e.g. overrides that no longer match the data
+    pub Warnings: Vec<String>,
+}
+
+// Reports the heroes that could not be read, the rest are added to InOutHeroes.
+// Heroes without a base attack speed use DEFAULT_BASE_ATTACK_SPEED and are reported as a warning.
+pub fn GetHeroesData(JsonData: &str, InOutHeroes: &mut Vec<Hero>) -> Result<ParseReport>
+{
+    let allHeroes: Map<String, Value> = ParseJson(HERO_DATA_FILE.SnapshotPath, JsonData)?;
+    let mut errors: Vec<Error> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+    let mut defaultAttackSpeedHeroes: Vec<String> = Vec::new();
+
+    // Loop through all heroes and add them to the hero list
+    for (key, heroValue) in allHeroes
+    {
+        // Name the hero in errors if we can
+        let heroKey = match heroValue.get("localized_name").and_then(|v| v.as_str())
+        {
+            Some(name) => format!("hero {} ({})", key, name),
+            None => format!("hero {}", key),
+        };
+
+        let hero: HeroRecord = match FromValue(&heroKey, heroValue)
+        {
+            Ok(hero) => hero,
+            Err(e) =>
+            {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        let primaryAttribute = match PrimaryAttribute::FromDataName(&hero.primary_attr)
+        {
+            Some(attribute) => attribute,
+            None =>
+            {
+                let message = format!("unknown primary attribute \"{}\"", hero.primary_attr);
+                errors.push(Error::Schema { Key: heroKey, Path: "primary_attr".to_string(), Message: message });
+                continue;
+            }
+        };
+
+        let baseAttackSpeed = match hero.base_attack_time
+        {
+            Some(attackSpeed) => attackSpeed as i32,
+            None =>
+            {
+                defaultAttackSpeedHeroes.push(hero.localized_name.clone());
+                DEFAULT_BASE_ATTACK_SPEED
+            }
+        };
+
+        let aHero: Hero = Hero
+        {
+            Name: hero.localized_name,
+            PrimaryAttribute: primaryAttribute,
+            AttackType: hero.attack_type,
+            BAT: hero.attack_rate,
+            BaseAttackSpeed: baseAttackSpeed,
+            BaseStr: hero.base_str.unwrap_or(0.0),
+            BaseAgi: hero.base_agi.unwrap_or(0.0),
+            BaseInt: hero.base_int.unwrap_or(0.0),
+            StrGain: hero.str_gain.unwrap_or(0.0),
+            AgiGain: hero.agi_gain.unwrap_or(0.0),
+            IntGain: hero.int_gain.unwrap_or(0.0),
+            BaseAttackMin: hero.base_attack_min.unwrap_or(0.0) as i32,
+            BaseAttackMax: hero.base_attack_max.unwrap_or(0.0) as i32,
+            BaseArmor: hero.base_armor.unwrap_or(0.0),
+            BaseMagicResist: hero.base_mr.unwrap_or(0.0) / 100.0,
+            AttackRange: hero.attack_range.unwrap_or(0.0) as i32,
+            ProjectileSpeed: hero.projectile_speed.unwrap_or(0.0) as i32,
+            AttackPoint: hero.attack_point.unwrap_or(0.0),
+            MoveSpeed: hero.move_speed.unwrap_or(0.0) as i32,
+            TurnRate: hero.turn_rate.unwrap_or(0.0),
+            BaseHealth: hero.base_health.unwrap_or(0.0),
+            BaseHealthRegen: hero.base_health_regen.unwrap_or(0.0),
+            BaseMana: hero.base_mana.unwrap_or(0.0),
+            BaseManaRegen: hero.base_mana_regen.unwrap_or(0.0),
+        };
+
+        InOutHeroes.push(aHero);
+    }
+
+    if !defaultAttackSpeedHeroes.is_empty()
+    {
+        warnings.push(format!("no base attack speed for {} heroes, using {}: {}", defaultAttackSpeedHeroes.len(), DEFAULT_BASE_ATTACK_SPEED, defaultAttackSpeedHeroes.join(", ")));
+    }
+
+    Ok(ParseReport { Skipped: errors, Warnings: warnings })
+}
+
+// All levels of a value, "10 20 30" -> [10, 20, 30]
+pub fn GetLevelValues(InValue: &Value) -> Vec<f32>
+{
+    match InValue
+    {
+        Value::Number(n) => vec![n.as_f64().unwrap() as f32],
+        Value::String(text) => text.split_whitespace().filter_map(|v| v.parse::<f32>().ok()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn GetItemMaxLevel(Entry: &AbilityEntry, Specials: &Map<String, Value>) -> i32
+{
+    let mut maxLevel = Entry.MaxUpgradeLevel.unwrap_or(1.0) as i32;
+
+    for value in Specials.values()
+    {
+        maxLevel = maxLevel.max(GetLevelValues(value).len() as i32);
+    }
+
+    maxLevel
+}
+
+// Picks the value for Level out of every space separated value, levels past the last value use the last one
+fn GetSpecialsAtLevel(Specials: &Map<String, Value>, Level: i32) -> Map<String, Value>
+{
+    Specials.iter().map(|(key, value)|
+    {
+        let levelValue = match value
+        {
+            Value::String(text) if text.split_whitespace().count() > 1 =>
+            {
+                let values: Vec<&str> = text.split_whitespace().collect();
+                let index = ((Level - 1).max(0) as usize).min(values.len() - 1);
+                Value::String(values[index].to_string())
+            }
+            v => v.clone(),
+        };
+        (key.clone(), levelValue)
+    }).collect()
+}
+
+// Sets Stat from an optional special value divided by Divisor (100 for percentages). Returns whether the value was there.
+fn SetStat<T: FromStat>(Stat: &mut T, InValue: Option<f32>, Divisor: f32) -> bool
+{
+    match InValue
+    {
+        Some(v) =>
+        {
+            *Stat = T::FromStat(v / Divisor);
+            true
+        }
+        None => false,
+    }
+}
+
+trait FromStat
+{
+    fn FromStat(InValue: f32) -> Self;
+}
+
+impl FromStat for i32
+{
+    fn FromStat(InValue: f32) -> i32
+    {
+        InValue as i32
+    }
+}
+
+impl FromStat for f32
+{
+    fn FromStat(InValue: f32) -> f32
+    {
+        InValue
+    }
+}
+
+// Copies the specials into the item, returns whether the item has any stat we care about
+fn ApplyItemSpecials(Specials: &ItemSpecials, InOutItem: &mut Item) -> bool
+{
+    let mut isUseful = false;
+
+    isUseful |= SetStat(&mut InOutItem.Str, Specials.bonus_strength, 1.0);
+    isUseful |= SetStat(&mut InOutItem.Agi, Specials.bonus_agility, 1.0);
+    isUseful |= SetStat(&mut InOutItem.Int, Specials.bonus_intellect, 1.0);
+
+    // Str,Agi,Int
+    if let Some(allStats) = Specials.bonus_all_stats.filter(|v| *v != 0.0)
+    {
+        InOutItem.Str += allStats as i32;
+        InOutItem.Agi += allStats as i32;
+        InOutItem.Int += allStats as i32;
+        isUseful = true;
+    }
+
+    isUseful |= SetStat(&mut InOutItem.Damage, Specials.bonus_damage, 1.0);
+    isUseful |= SetStat(&mut InOutItem.Damage_Melee, Specials.bonus_damage_melee, 1.0);
+    isUseful |= SetStat(&mut InOutItem.Damage_Ranged, Specials.bonus_damage_range, 1.0);
+
+    isUseful |= SetStat(&mut InOutItem.AttackSpeed, Specials.bonus_attack_speed, 1.0);
+
+    isUseful |= SetStat(&mut InOutItem.ArmorCorruption, Specials.corruption_armor, 1.0);
+
+    // Magic Damage (& bonus_chance_damage) since their calculation is the same
+    isUseful |= SetStat(&mut InOutItem.MagicDamage, Specials.chain_damage, 1.0);
+    isUseful |= SetStat(&mut InOutItem.MagicDamage, Specials.bonus_chance_damage, 1.0);
+
+    // Magic % (&bonus_chance)
+    for chance in [Specials.chain_chance, Specials.bonus_chance]
+    {
+        if SetStat(&mut InOutItem.MagicChance_Melee, chance, 100.0)
+        {
+            InOutItem.MagicChance_Ranged = InOutItem.MagicChance_Melee;
+            isUseful = true;
+        }
+    }
+
+    // Magic % Bash
+    isUseful |= SetStat(&mut InOutItem.MagicChance_Melee, Specials.bash_chance_melee, 100.0);
+    isUseful |= SetStat(&mut InOutItem.MagicChance_Ranged, Specials.bash_chance_ranged, 100.0);
+
+    isUseful |= SetStat(&mut InOutItem.CritMultiplier, Specials.crit_multiplier, 100.0);
+    isUseful |= SetStat(&mut InOutItem.CritChance, Specials.crit_chance, 100.0);
+
+    // Defensive
+    isUseful |= SetStat(&mut InOutItem.Armor, Specials.bonus_armor, 1.0);
+    isUseful |= SetStat(&mut InOutItem.Evasion, Specials.bonus_evasion, 100.0);
+    // Cloak/Hood/Pipe each name their magic resistance differently
+    for magicResistance in [Specials.bonus_magical_armor, Specials.bonus_spell_resist, Specials.magic_resistance]
+    {
+        isUseful |= SetStat(&mut InOutItem.MagicResistance, magicResistance, 100.0);
+    }
+    for statusResistance in [Specials.status_resistance, Specials.bonus_status_resistance]
+    {
+        isUseful |= SetStat(&mut InOutItem.StatusResistance, statusResistance, 100.0);
+    }
+    for slowResistance in [Specials.slow_resistance, Specials.bonus_slow_resistance]
+    {
+        isUseful |= SetStat(&mut InOutItem.SlowResistance, slowResistance, 100.0);
+    }
+
+    // Sustain
+    isUseful |= SetStat(&mut InOutItem.Health, Specials.bonus_health, 1.0);
+    isUseful |= SetStat(&mut InOutItem.Mana, Specials.bonus_mana, 1.0);
+    isUseful |= SetStat(&mut InOutItem.HealthRegen, Specials.bonus_health_regen, 1.0);
+    isUseful |= SetStat(&mut InOutItem.ManaRegen, Specials.bonus_mana_regen, 1.0);
+    isUseful |= SetStat(&mut InOutItem.Lifesteal, Specials.lifesteal_percent, 100.0);
+    isUseful |= SetStat(&mut InOutItem.SpellLifesteal, Specials.spell_lifesteal, 100.0);
+
+    // Mobility
+    isUseful |= SetStat(&mut InOutItem.MoveSpeed, Specials.bonus_movement_speed, 1.0);
+    for moveSpeedPercent in [Specials.movement_speed_percent_bonus, Specials.bonus_movement_speed_pct]
+    {
+        isUseful |= SetStat(&mut InOutItem.MoveSpeedPercent, moveSpeedPercent, 100.0);
+    }
+    // Dragon Lance/Hurricane Pike only give range to ranged heroes
+    isUseful |= SetStat(&mut InOutItem.AttackRange_Ranged, Specials.base_attack_range, 1.0);
+    if SetStat(&mut InOutItem.AttackRange_Melee, Specials.bonus_attack_range, 1.0)
+    {
+        InOutItem.AttackRange_Ranged = InOutItem.AttackRange_Melee;
+        isUseful = true;
+    }
+    isUseful |= SetStat(&mut InOutItem.ProjectileSpeed, Specials.bonus_projectile_speed, 1.0);
+
+    // Caster
+    for spellAmp in [Specials.spell_amp, Specials.bonus_spell_amp]
+    {
+        isUseful |= SetStat(&mut InOutItem.SpellAmp, spellAmp, 100.0);
+    }
+    for castRange in [Specials.cast_range_bonus, Specials.bonus_cast_range]
+    {
+        isUseful |= SetStat(&mut InOutItem.CastRange, castRange, 1.0);
+    }
+    isUseful |= SetStat(&mut InOutItem.CooldownReduction, Specials.bonus_cooldown, 100.0);
+
+    isUseful |= ApplyOnHitSpecials(Specials, &mut InOutItem.OnHit);
+
+    isUseful
+}
+
+// The on-hit part of ApplyItemSpecials. MagicDamage/MagicChance_* squash chain lightning and procs into two columns
+// for the xlsm, OnHit keeps them apart.
+fn ApplyOnHitSpecials(Specials: &ItemSpecials, OnHit: &mut OnHitEffects) -> bool
+{
+    let mut isUseful = false;
+
+    isUseful |= SetStat(&mut OnHit.CleavePercent, Specials.cleave_damage_percent, 100.0);
+    SetStat(&mut OnHit.CleaveRadius, Specials.cleave_distance, 1.0);
+
+    isUseful |= SetStat(&mut OnHit.ChainChance, Specials.chain_chance, 100.0);
+    isUseful |= SetStat(&mut OnHit.ChainDamage, Specials.chain_damage, 1.0);
+    SetStat(&mut OnHit.ChainStrikes, Specials.chain_strikes, 1.0);
+
+    // bonus_chance_damage is the bash damage for bashers and the pierce damage for Monkey King Bar
+    let isBasher = SetStat(&mut OnHit.BashChance_Melee, Specials.bash_chance_melee, 100.0)
+        | SetStat(&mut OnHit.BashChance_Ranged, Specials.bash_chance_ranged, 100.0);
+    if isBasher
+    {
+        SetStat(&mut OnHit.BashDamage, Specials.bonus_chance_damage, 1.0);
+        for stun in [Specials.bash_duration, Specials.bash_stun]
+        {
+            SetStat(&mut OnHit.BashStun, stun, 1.0);
+        }
+        isUseful = true;
+    }
+    else if SetStat(&mut OnHit.TrueStrikeChance, Specials.bonus_chance, 100.0)
+    {
+        SetStat(&mut OnHit.PierceDamage, Specials.bonus_chance_damage, 1.0);
+        isUseful = true;
+    }
+
+    isUseful |= SetStat(&mut OnHit.SlowMoveSpeed_Melee, Specials.cold_slow_melee, 100.0);
+    isUseful |= SetStat(&mut OnHit.SlowMoveSpeed_Ranged, Specials.cold_slow_ranged, 100.0);
+    isUseful |= SetStat(&mut OnHit.SlowAttackSpeed_Melee, Specials.cold_attack_slow_melee, 1.0);
+    isUseful |= SetStat(&mut OnHit.SlowAttackSpeed_Ranged, Specials.cold_attack_slow_ranged, 1.0);
+    SetStat(&mut OnHit.SlowDuration, Specials.cold_duration, 1.0);
+
+    SetStat(&mut OnHit.CorruptionDuration, Specials.corruption_duration, 1.0);
+
+    isUseful
+}
+
+// Value of a space separated per level value at Level
+fn GetValueAtLevel(InValue: &Value, Level: i32) -> Option<f32>
+{
+    let values = GetLevelValues(InValue);
+    let index = ((Level - 1).max(0) as usize).min(values.len().saturating_sub(1));
+    values.get(index).copied()
+}
+
+// Moves AbilityKeys (the specials that only apply while the ability is used) out of LevelSpecials into the ItemAbility.
+// The ability also lists every special that is not one of StatKeys, like Bloodthorn's silence_duration.
+fn GetItemAbility(Entry: &AbilityEntry, AbilityKeys: &[String], StatKeys: &[String], LevelSpecials: &mut Map<String, Value>, Level: i32) -> Option<ItemAbility>
+{
+    let mut specials: BTreeMap<String, f32> = AbilityKeys.iter()
+        .filter_map(|key| LevelSpecials.remove(key).and_then(|v| GetValueAtLevel(&v, 1)).map(|v| (key.clone(), v)))
+        .collect();
+
+    let cooldown = Entry.AbilityCooldown.as_ref().and_then(|v| GetValueAtLevel(v, Level)).unwrap_or(0.0);
+    let manaCost = Entry.AbilityManaCost.as_ref().and_then(|v| GetValueAtLevel(v, Level)).unwrap_or(0.0);
+    if cooldown == 0.0 && manaCost == 0.0 && specials.is_empty()
+    {
+        return None;
+    }
+
+    for (key, value) in LevelSpecials.iter()
+    {
+        if !StatKeys.contains(key)
+        {
+            if let Some(value) = GetValueAtLevel(value, 1)
+            {
+                specials.insert(key.clone(), value);
+            }
+        }
+    }
+
+    // "duration", or the first "..._duration" when the item has no plain one
+    let duration = LevelSpecials.get("duration")
+        .or_else(|| LevelSpecials.iter().find(|(key, _)| key.ends_with("_duration")).map(|(_, v)| v))
+        .and_then(|v| GetValueAtLevel(v, 1))
+        .unwrap_or(0.0);
+
+    Some(ItemAbility
+    {
+        IsPassive: Entry.AbilityBehavior.as_deref().is_some_and(|behavior| behavior.contains("DOTA_ABILITY_BEHAVIOR_PASSIVE")),
+        Cooldown: cooldown,
+        ManaCost: manaCost,
+        Duration: duration,
+        Specials: specials,
+    })
+}
+
+// Reports the items that could not be read, the rest are added to InOutItems. InOverrides fixes the upstream data first,
+// overrides that name items or values the data does not have are reported as warnings.
+pub fn GetItemStats(JsonData: &str, InOverrides: &Overrides, InOutItems: &mut Vec<Item>, IgnoreUselessItems: bool) -> Result<ParseReport>
+{
+    let mut parsed: Map<String, Value> = ParseJson(ITEM_DATA_FILE.SnapshotPath, JsonData)?;
+    let mut allItems: Map<String, Value> = match parsed.remove("DOTAAbilities")
+    {
+        Some(Value::Object(abilities)) => abilities,
+        _ => return Err(Error::Schema { Key: ITEM_DATA_FILE.SnapshotPath.to_string(), Path: "DOTAAbilities".to_string(), Message: "missing or not an object".to_string() }),
+    };
+    allItems.remove("Version");
+    let statKeys = ItemSpecials::StatKeys();
+    let mut errors: Vec<Error> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    for itemName in InOverrides.Items.keys()
+    {
+        if !allItems.contains_key(itemName)
+        {
+            warnings.push(format!("override for {}, which is not in the item data", itemName));
+        }
+    }
+
+    // Loop through all items, if matches criterias, add it to the item list
+    for item in &allItems
+    {
+        let entry: AbilityEntry = match FromValue(item.0, item.1.clone())
+        {
+            Ok(entry) => entry,
+            Err(e) =>
+            {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        // Early Continue
+        if entry.IsObsolete.unwrap_or(0.0) > 0.0
+        {
+            continue;
+        }
+
+        let mut aItem: Item = Item{Name: item.0.clone(), Key: item.0.clone(), ..Default::default()};
+        aItem.Cost = entry.ItemCost.unwrap_or(0.0) as i32;
+        aItem.IsNeutralItem = entry.ItemIsNeutralDrop == Some(1.0);
+        aItem.IsInDropPool = aItem.IsNeutralItem;
+        // Tango, Clarity, Tome of Knowledge... are used up, ItemPermanent 0 means the item goes away with its charges
+        aItem.IsConsumable = entry.ItemQuality.as_deref() == Some("consumable") || entry.ItemPermanent == Some(0.0);
+        aItem.Charges = entry.ItemInitialCharges.unwrap_or(0.0) as i32;
+
+        let mut allLevelsSpecials = entry.Specials();
+        let missingKeys = InOverrides.Apply(item.0, &mut allLevelsSpecials);
+        if !missingKeys.is_empty()
+        {
+            warnings.push(format!("override for {} names values it does not have: {}", item.0, missingKeys.join(", ")));
+        }
+
+        // Multi level items either come as one entry per level (item_dagon_3 has ItemBaseLevel 3)
+        // or as a single entry with space separated values, which gives one item per level
+        let maxLevel = GetItemMaxLevel(&entry, &allLevelsSpecials);
+        let levels = match entry.ItemBaseLevel
+        {
+            Some(baseLevel) => vec![baseLevel as i32],
+            None => (1..=maxLevel).collect(),
+        };
+
+        aItem.MaxLevel = maxLevel;
+        aItem.LevelValues = allLevelsSpecials.iter()
+            .map(|(key, value)| (key.clone(), GetLevelValues(value)))
+            .filter(|(_, values)| values.len() > 1)
+            .collect();
+        let baseItem = aItem;
+
+        'levels: for level in levels
+        {
+            let mut aItem = baseItem.clone();
+            aItem.Level = level;
+
+            let mut levelSpecials = GetSpecialsAtLevel(&allLevelsSpecials, level);
+            aItem.Ability = GetItemAbility(&entry, InOverrides.AbilityKeys(item.0), &statKeys, &mut levelSpecials, level);
+
+            // Moon Shard and the like get a second item with only the stats they keep once consumed
+            let consumedKeys = InOverrides.ConsumedKeys(item.0);
+            aItem.IsPermanentWhenConsumed = !consumedKeys.is_empty();
+            // Consuming it is how it becomes permanent, whatever ItemQuality says
+            aItem.IsConsumable |= aItem.IsPermanentWhenConsumed;
+            let mut variants: Vec<(Item, Map<String, Value>)> = Vec::new();
+            if aItem.IsPermanentWhenConsumed
+            {
+                let consumedSpecials: Map<String, Value> = consumedKeys.iter()
+                    .filter_map(|(key, stat)| levelSpecials.get(key).map(|value| (stat.clone(), value.clone())))
+                    .collect();
+                let consumedItem = Item{IsConsumable: true, IsConsumed: true, Ability: None, ..aItem.clone()};
+                variants.push((consumedItem, consumedSpecials));
+            }
+            variants.insert(0, (aItem, levelSpecials));
+
+            for (mut aItem, variantSpecials) in variants
+            {
+                let specials: ItemSpecials = match FromValue(item.0, Value::Object(variantSpecials))
+                {
+                    Ok(specials) => specials,
+                    Err(e) =>
+                    {
+                        errors.push(e);
+                        break 'levels;
+                    }
+                };
+
+                // Tome of Knowledge, Clarity... are bought for what using them does, not for stats
+                let isUsedUp = aItem.IsConsumable || aItem.Charges > 0;
+                let isUselessItem = !ApplyItemSpecials(&specials, &mut aItem) && !isUsedUp;
+
+                // Set IsUselessItem
+                aItem.IsUselessItem = isUselessItem;
+
+                // If ignore useless items
+                if IgnoreUselessItems && isUselessItem
+                {
+                    continue;
+                }
+
+                // Add to list
+                InOutItems.push(aItem);
+            }
+        }
+    }
+
+    Ok(ParseReport { Skipped: errors, Warnings: warnings })
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::model::{GetAbilityDescription, GetItemDisplayName};
+
+    fn ParseFixture(JsonData: &str) -> Vec<Item>
+    {
+        let mut items: Vec<Item> = Vec::new();
+        let report = GetItemStats(JsonData, &Overrides::Builtin(), &mut items, true).unwrap();
+        assert!(report.Skipped.is_empty(), "{:?}", report.Skipped);
+        items
+    }
+
+    fn FindItem<'a>(Items: &'a [Item], Name: &str) -> &'a Item
+    {
+        Items.iter().find(|item| item.Name == Name).unwrap_or_else(|| panic!("{} missing", Name))
+    }
+
+    fn CheckFixtureItems(Items: &[Item])
+    {
+        // Blink has no stats we care about and the obsolete item is skipped
+        assert_eq!(Items.len(), 3);
+
+        let ogreAxe = FindItem(Items, "item_ogre_axe");
+        assert_eq!(ogreAxe.Str, 10);
+        assert_eq!(ogreAxe.Cost, 1000);
+
+        let crystalys = FindItem(Items, "item_lesser_crit");
+        assert_eq!(crystalys.Damage, 15);
+        assert_eq!(crystalys.CritChance, 0.3);
+        assert_eq!(crystalys.CritMultiplier, 1.6);
+
+        let maelstrom = FindItem(Items, "item_maelstrom");
+        assert_eq!(maelstrom.Damage, 24);
+        assert_eq!(maelstrom.MagicDamage, 120);
+        assert_eq!(maelstrom.MagicChance_Melee, 0.3);
+        assert_eq!(maelstrom.MagicChance_Ranged, 0.3);
+        assert_eq!((maelstrom.OnHit.ChainChance, maelstrom.OnHit.ChainDamage, maelstrom.OnHit.ChainStrikes), (0.3, 120.0, 4));
+    }
+
+    #[test]
+    fn ParsesAbilitySpecial()
+    {
+        CheckFixtureItems(&ParseFixture(include_str!("../tests/fixtures/items_ability_special.json")));
+    }
+
+    #[test]
+    fn ParsesAbilityValues()
+    {
+        CheckFixtureItems(&ParseFixture(include_str!("../tests/fixtures/items_ability_values.json")));
+    }
+
+    #[test]
+    fn ParsesMultiLevelItems()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_dagon_3": {"ItemCost": "3450", "ItemBaseLevel": "3", "MaxUpgradeLevel": "5",
+                "AbilitySpecial": [{"bonus_intellect": "7 9 11 13 15"}]},
+            "item_necronomicon": {"ItemCost": "2550",
+                "AbilityValues": {"bonus_strength": "8 12 16"}}
+        }}"#);
+
+        let dagon = FindItem(&items, "item_dagon_3");
+        assert_eq!((dagon.Level, dagon.MaxLevel, dagon.Int), (3, 5, 11));
+        assert_eq!(dagon.LevelValues["bonus_intellect"], vec![7.0, 9.0, 11.0, 13.0, 15.0]);
+        assert_eq!(GetItemDisplayName(dagon), "item_dagon_3 (Level 3)");
+
+        let necronomicons: Vec<&Item> = items.iter().filter(|item| item.Name == "item_necronomicon").collect();
+        assert_eq!(necronomicons.iter().map(|item| (item.Level, item.Str)).collect::<Vec<_>>(), vec![(1, 8), (2, 12), (3, 16)]);
+    }
+
+    #[test]
+    fn ParsesDefensiveStats()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_assault": {"AbilityValues": {"bonus_attack_speed": "30", "bonus_armor": "10"}},
+            "item_butterfly": {"AbilityValues": {"bonus_agility": "35", "bonus_evasion": "35"}},
+            "item_cloak": {"AbilityValues": {"bonus_magical_armor": "15"}},
+            "item_sange": {"AbilityValues": {"bonus_strength": "16", "status_resistance": "16", "slow_resistance": "20"}}
+        }}"#);
+
+        assert_eq!(FindItem(&items, "item_assault").Armor, 10.0);
+        assert_eq!(FindItem(&items, "item_butterfly").Evasion, 0.35);
+        // Only defensive stats is still useful
+        assert_eq!(FindItem(&items, "item_cloak").MagicResistance, 0.15);
+        let sange = FindItem(&items, "item_sange");
+        assert_eq!((sange.StatusResistance, sange.SlowResistance), (0.16, 0.2));
+    }
+
+    #[test]
+    fn ParsesSustainStats()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_vanguard": {"AbilityValues": {"bonus_health": "250", "bonus_health_regen": "7"}},
+            "item_lifesteal": {"AbilityValues": {"lifesteal_percent": "18"}},
+            "item_kaya": {"AbilityValues": {"bonus_intellect": "16", "spell_lifesteal": "10", "bonus_mana_regen": "1.5"}}
+        }}"#);
+
+        let vanguard = FindItem(&items, "item_vanguard");
+        assert_eq!((vanguard.Health, vanguard.HealthRegen), (250, 7.0));
+        assert_eq!(FindItem(&items, "item_lifesteal").Lifesteal, 0.18);
+        let kaya = FindItem(&items, "item_kaya");
+        assert_eq!((kaya.SpellLifesteal, kaya.ManaRegen), (0.1, 1.5));
+    }
+
+    #[test]
+    fn ParsesMobilityAndCasterStats()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_boots": {"AbilityValues": {"bonus_movement_speed": "45"}},
+            "item_yasha": {"AbilityValues": {"bonus_agility": "16", "movement_speed_percent_bonus": "8"}},
+            "item_dragon_lance": {"AbilityValues": {"bonus_agility": "12", "base_attack_range": "150"}},
+            "item_octarine_core": {"AbilityValues": {"bonus_cooldown": "25", "cast_range_bonus": "225"}},
+            "item_kaya": {"AbilityValues": {"bonus_intellect": "16", "spell_amp": "8"}}
+        }}"#);
+
+        assert_eq!(FindItem(&items, "item_boots").MoveSpeed, 45);
+        assert_eq!(FindItem(&items, "item_yasha").MoveSpeedPercent, 0.08);
+        let lance = FindItem(&items, "item_dragon_lance");
+        assert_eq!((lance.AttackRange_Melee, lance.AttackRange_Ranged), (0, 150));
+        let octarine = FindItem(&items, "item_octarine_core");
+        assert_eq!((octarine.CooldownReduction, octarine.CastRange), (0.25, 225));
+        assert_eq!(FindItem(&items, "item_kaya").SpellAmp, 0.08);
+    }
+
+    #[test]
+    fn ParsesOnHitEffects()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_bfury": {"AbilityValues": {"bonus_damage": "55", "cleave_damage_percent": "70", "cleave_distance": "650"}},
+            "item_basher": {"AbilityValues": {"bash_chance_melee": "25", "bash_chance_ranged": "10", "bonus_chance_damage": "100", "bash_duration": "1.5"}},
+            "item_monkey_king_bar": {"AbilityValues": {"bonus_damage": "40", "bonus_chance": "80", "bonus_chance_damage": "70"}},
+            "item_skadi": {"AbilityValues": {"bonus_all_stats": "22", "cold_slow_melee": "20", "cold_slow_ranged": "10", "cold_attack_slow_melee": "20", "cold_attack_slow_ranged": "10", "cold_duration": "3"}}
+        }}"#);
+
+        let fury = &FindItem(&items, "item_bfury").OnHit;
+        assert_eq!((fury.CleavePercent, fury.CleaveRadius), (0.7, 650.0));
+
+        let basher = &FindItem(&items, "item_basher").OnHit;
+        assert_eq!((basher.BashChance_Melee, basher.BashChance_Ranged, basher.BashDamage, basher.BashStun), (0.25, 0.1, 100.0, 1.5));
+        assert_eq!(basher.TrueStrikeChance, 0.0);
+
+        let mkb = &FindItem(&items, "item_monkey_king_bar").OnHit;
+        assert_eq!((mkb.TrueStrikeChance, mkb.PierceDamage, mkb.BashDamage), (0.8, 70.0, 0.0));
+
+        let skadi = &FindItem(&items, "item_skadi").OnHit;
+        assert_eq!((skadi.SlowMoveSpeed_Melee, skadi.SlowAttackSpeed_Ranged, skadi.SlowDuration), (0.2, 10, 3.0));
+    }
+
+    #[test]
+    fn AbilityValuesAreNotStats()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_bloodthorn": {
+                "AbilityBehavior": "DOTA_ABILITY_BEHAVIOR_UNIT_TARGET | DOTA_ABILITY_BEHAVIOR_IMMEDIATE",
+                "AbilityCooldown": "15", "AbilityManaCost": "100",
+                "AbilityValues": {"bonus_intellect": "20", "crit_multiplier": "175", "silence_duration": "5"}
+            },
+            "item_basher": {
+                "AbilityBehavior": "DOTA_ABILITY_BEHAVIOR_PASSIVE", "AbilityCooldown": "2.3",
+                "AbilityValues": {"bash_chance_melee": "25", "bash_duration": "1.5"}
+            },
+            "item_lesser_crit": {"AbilityValues": {"crit_chance": "30", "crit_multiplier": "160"}}
+        }}"#);
+
+        let bloodthorn = FindItem(&items, "item_bloodthorn");
+        assert_eq!((bloodthorn.Int, bloodthorn.CritMultiplier), (20, 1.0));
+        let active = bloodthorn.Ability.as_ref().unwrap();
+        assert_eq!((active.IsPassive, active.Cooldown, active.ManaCost, active.Duration), (false, 15.0, 100.0, 5.0));
+        assert_eq!(GetAbilityDescription(active), "Active: 15s cooldown, 100 mana, 5s, crit_multiplier 175, silence_duration 5");
+
+        let passive = FindItem(&items, "item_basher").Ability.as_ref().unwrap();
+        assert_eq!((passive.IsPassive, passive.Cooldown, passive.Duration), (true, 2.3, 1.5));
+        // Items without cooldown or mana cost have no ability
+        assert_eq!(FindItem(&items, "item_lesser_crit").Ability, None);
+    }
+
+    #[test]
+    fn OverridesFixUpstreamData()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_orb_of_corrosion": {"AbilityValues": {"armor": "-3", "duration": "3", "bonus_health_regen": "3"}},
+            "item_hurricane_pike": {"AbilityCooldown": "23", "AbilityValues": {"bonus_agility": "20", "bonus_attack_speed": "100"}}
+        }}"#);
+
+        let orb = FindItem(&items, "item_orb_of_corrosion");
+        assert_eq!((orb.ArmorCorruption, orb.OnHit.CorruptionDuration, orb.Armor), (3, 3.0, 0.0));
+
+        let pike = FindItem(&items, "item_hurricane_pike");
+        assert_eq!(pike.AttackSpeed, 0);
+        assert_eq!(pike.Ability.as_ref().unwrap().Specials.get("bonus_attack_speed"), Some(&100.0));
+    }
+
+    #[test]
+    fn WarnsAboutStaleOverrides()
+    {
+        let overrides = Overrides::Parse("test.toml", r#"
+            [item_orb_of_corrosion]
+            rename = { armor = "corruption_armor" }
+            [item_removed]
+            ignore = ["bonus_damage"]
+        "#).unwrap();
+
+        let mut items: Vec<Item> = Vec::new();
+        let report = GetItemStats(r#"{"DOTAAbilities": {"item_orb_of_corrosion": {"AbilityValues": {"corruption": "-3"}}}}"#, &overrides, &mut items, false).unwrap();
+        assert_eq!(report.Warnings, [
+            "override for item_removed, which is not in the item data",
+            "override for item_orb_of_corrosion names values it does not have: armor",
+        ]);
+    }
+
+    #[test]
+    fn ReadsComponentsAndRecipeCost()
+    {
+        let mut items = ParseFixture(include_str!("../tests/fixtures/items_ability_values.json"));
+        GetRealItemNames(r#"{
+            "lesser_crit": {"dname": "Crystalys", "cost": 2000, "components": ["broadsword", "blades_of_attack"]},
+            "broadsword": {"dname": "Broadsword", "cost": 1000, "components": null},
+            "blades_of_attack": {"dname": "Blades of Attack", "cost": 450}
+        }"#, &mut items).unwrap();
+
+        let crystalys = FindItem(&items, "Crystalys");
+        assert_eq!(crystalys.Key, "item_lesser_crit");
+        assert_eq!(crystalys.Components, ["item_broadsword", "item_blades_of_attack"]);
+        assert_eq!(crystalys.RecipeCost, 550);
+        assert!(FindItem(&items, "item_ogre_axe").Components.is_empty());
+    }
+
+    #[test]
+    fn ReadsNeutralTiers()
+    {
+        let mut items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_broom_handle": {"ItemIsNeutralDrop": "1", "AbilityValues": {"bonus_damage": "18"}},
+            "item_ironwood_tree": {"AbilityValues": {"bonus_all_stats": "7"}}
+        }}"#);
+        GetRealItemNames(r#"{
+            "broom_handle": {"dname": "Broom Handle", "tier": 1},
+            "ironwood_tree": {"dname": "Ironwood Tree", "tier": "1"}
+        }"#, &mut items).unwrap();
+
+        let broom = FindItem(&items, "Broom Handle");
+        assert_eq!((broom.IsNeutralItem, broom.NeutralTier, broom.AvailableMinute, broom.IsInDropPool), (true, 1, 5, true));
+        // Retired from the drop pool
+        let ironwood = FindItem(&items, "Ironwood Tree");
+        assert_eq!((ironwood.IsNeutralItem, ironwood.NeutralTier, ironwood.IsInDropPool), (true, 1, false));
+    }
+
+    #[test]
+    fn ConsumablesAndConsumedVariants()
+    {
+        let items = ParseFixture(r#"{"DOTAAbilities": {
+            "item_moon_shard": {"AbilityValues": {"bonus_attack_speed": "140", "consumed_bonus": "60", "consumed_bonus_night_vision": "100"}},
+            "item_clarity": {"ItemQuality": "consumable", "ItemPermanent": "0", "ItemInitialCharges": "1", "AbilityValues": {"mana_regen": "6"}},
+            "item_tome_of_knowledge": {"ItemQuality": "consumable", "AbilityValues": {"xp_bonus": "700"}},
+            "item_black_king_bar": {"ItemInitialCharges": "0", "AbilityValues": {"bonus_strength": "10"}}
+        }}"#);
+
+        let variants: Vec<&Item> = items.iter().filter(|item| item.Name == "item_moon_shard").collect();
+        assert_eq!(variants.len(), 2);
+        assert_eq!((variants[0].AttackSpeed, variants[0].IsConsumed, variants[0].IsPermanentWhenConsumed), (140, false, true));
+        assert!(variants[0].IsConsumable);
+        assert_eq!((variants[1].AttackSpeed, variants[1].IsConsumed, variants[1].IsConsumable), (60, true, true));
+        assert_eq!(GetItemDisplayName(variants[1]), "item_moon_shard (Consumed)");
+
+        // Clarity and Tome of Knowledge have no stat we model but are kept as consumables, BKB is not consumable
+        assert!(!FindItem(&items, "item_clarity").IsUselessItem);
+        assert!(FindItem(&items, "item_tome_of_knowledge").IsConsumable);
+        assert!(!FindItem(&items, "item_black_king_bar").IsConsumable);
+
+        let mut allItems: Vec<Item> = Vec::new();
+        GetItemStats(r#"{"DOTAAbilities": {"item_clarity": {"ItemQuality": "consumable", "ItemInitialCharges": "1"}}}"#, &Overrides::Builtin(), &mut allItems, false).unwrap();
+        assert_eq!((allItems[0].IsConsumable, allItems[0].Charges), (true, 1));
+    }
+
+    #[test]
+    fn ReadsHeroBaseStats()
+    {
+        let mut heroes: Vec<Hero> = Vec::new();
+        let report = GetHeroesData(r#"{
+            "1": {"localized_name": "Anti-Mage", "primary_attr": "agi", "attack_type": "Melee", "attack_rate": 1.4,
+                  "base_str": 21, "base_agi": 24, "base_int": 12, "str_gain": 1.6, "agi_gain": 2.8, "int_gain": 1.8,
+                  "base_attack_min": 29, "base_attack_max": 33, "base_armor": 1, "base_mr": 25, "attack_range": 150,
+                  "projectile_speed": 0, "move_speed": 310, "turn_rate": null, "base_attack_time": 110,
+                  "attack_point": "0.3"},
+            "2": {"localized_name": "Axe", "primary_attr": "str", "attack_type": "Melee", "attack_rate": 1.7},
+            "3": {"localized_name": "Pangolier", "primary_attr": "all", "attack_type": "Melee", "attack_rate": 1.7},
+            "4": {"localized_name": "Future Hero", "primary_attr": "luck", "attack_type": "Melee", "attack_rate": 1.7}
+        }"#, &mut heroes).unwrap();
+
+        // Unknown attributes are reported instead of guessed
+        assert_eq!(report.Skipped.len(), 1);
+        assert_eq!(report.Skipped[0].to_string(), "hero 4 (Future Hero) at primary_attr: unknown primary attribute \"luck\"");
+        assert_eq!(heroes.iter().map(|hero| hero.PrimaryAttribute).collect::<Vec<_>>(),
+            [PrimaryAttribute::Agility, PrimaryAttribute::Strength, PrimaryAttribute::Universal]);
+
+        let antiMage = &heroes[0];
+        assert_eq!((antiMage.BaseAgi, antiMage.AgiGain, antiMage.BaseAttackMin, antiMage.BaseAttackMax), (24.0, 2.8, 29, 33));
+        assert_eq!((antiMage.BaseMagicResist, antiMage.AttackRange, antiMage.MoveSpeed, antiMage.TurnRate), (0.25, 150, 310, 0.0));
+        assert_eq!((antiMage.BaseAttackSpeed, antiMage.AttackPoint), (110, 0.3));
+        // Older data without the base stats still reads
+        assert_eq!((heroes[1].Name.as_str(), heroes[1].BaseStr, heroes[1].BaseAttackSpeed), ("Axe", 0.0, DEFAULT_BASE_ATTACK_SPEED));
+        assert_eq!(report.Warnings, ["no base attack speed for 2 heroes, using 100: Axe, Pangolier"]);
+    }
+
+    #[test]
+    fn CollectsBadItemsInsteadOfFailing()
+    {
+        let mut items: Vec<Item> = Vec::new();
+        let errors = GetItemStats(r#"{"DOTAAbilities": {
+            "item_ogre_axe": {"ItemCost": "1000", "AbilityValues": {"bonus_strength": "10"}},
+            "item_broken": {"ItemCost": "lots", "AbilityValues": {"bonus_strength": "10"}}
+        }}"#, &Overrides::Builtin(), &mut items, true).unwrap().Skipped;
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "item_broken at ItemCost: expected a number, got \"lots\"");
+    }
+
+    #[test]
+    fn BothSchemasGiveTheSameItems()
+    {
+        let special = ParseFixture(include_str!("../tests/fixtures/items_ability_special.json"));
+        let values = ParseFixture(include_str!("../tests/fixtures/items_ability_values.json"));
+        assert_eq!(special, values);
+    }
+}
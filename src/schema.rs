@@ -0,0 +1,308 @@
+// Typed models of the upstream json: d2vpkr's DOTAAbilities entries and dotaconstants' hero/item records.
+// Fields are named exactly like the json keys, so a new stat is one more field here.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// Accepts 10, 10.5, "10", "10.5" and "" (as None)
+pub fn NumberOrString<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)?
+    {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => Ok(n.as_f64().map(|v| v as f32)),
+        Some(Value::String(text)) if text.trim().is_empty() => Ok(None),
+        Some(Value::String(text)) => text.trim().parse::<f32>()
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("expected a number, got \"{}\"", text))),
+        Some(other) => Err(serde::de::Error::custom(format!("expected a number, got {}", other))),
+    }
+}
+
+// Same as NumberOrString, but the value must be there
+pub fn Number<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    NumberOrString(deserializer)?.ok_or_else(|| serde::de::Error::custom("expected a number, got nothing"))
+}
+
+// One entry of DOTAAbilities
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AbilityEntry
+{
+    #[serde(deserialize_with = "NumberOrString")]
+    pub IsObsolete: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub ItemCost: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub ItemIsNeutralDrop: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub ItemBaseLevel: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub MaxUpgradeLevel: Option<f32>,
+    // "consumable" for Tango, Clarity...
+    pub ItemQuality: Option<String>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub ItemPermanent: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub ItemInitialCharges: Option<f32>,
+    // "DOTA_ABILITY_BEHAVIOR_PASSIVE" or several behaviors separated by " | "
+    pub AbilityBehavior: Option<String>,
+    // Space separated per level like the specials
+    pub AbilityCooldown: Option<Value>,
+    pub AbilityManaCost: Option<Value>,
+    // d2vpkr has an array of single value maps, KeyValues files have a block keyed "01", "02", ...
+    pub AbilitySpecial: Option<Value>,
+    // Since 7.33, a value is either plain or a block with "value" plus special bonus overrides
+    pub AbilityValues: Option<Map<String, Value>>,
+}
+
+impl AbilityEntry
+{
+    // All special values of the ability in one map, with the raw (possibly space separated per level) values.
+    // For AbilityValues blocks only the base "value" is used.
+    pub fn Specials(&self) -> Map<String, Value>
+    {
+        let mut specials = Map::new();
+
+        let specialList: Vec<&Value> = match &self.AbilitySpecial
+        {
+            Some(Value::Array(list)) => list.iter().collect(),
+            Some(Value::Object(numbered)) => numbered.values().collect(),
+            _ => Vec::new(),
+        };
+        for special in specialList
+        {
+            if let Value::Object(special) = special
+            {
+                for (key, value) in special
+                {
+                    if key != "var_type"
+                    {
+                        specials.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+
+        for (key, value) in self.AbilityValues.iter().flatten()
+        {
+            let baseValue = match value
+            {
+                Value::Object(block) => match block.get("value")
+                {
+                    Some(v) => v.clone(),
+                    None => continue,
+                },
+                v => v.clone(),
+            };
+            specials.insert(key.clone(), baseValue);
+        }
+
+        specials
+    }
+}
+
+// The item special values we use, at a single level
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ItemSpecials
+{
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_strength: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_agility: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_intellect: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_all_stats: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_damage: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_damage_melee: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_damage_range: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_attack_speed: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub corruption_armor: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub chain_damage: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_chance_damage: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub chain_chance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_chance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bash_chance_melee: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bash_chance_ranged: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub crit_multiplier: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub crit_chance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_armor: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_evasion: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_magical_armor: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_spell_resist: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub magic_resistance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub status_resistance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_status_resistance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub slow_resistance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_slow_resistance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_health: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_mana: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_health_regen: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_mana_regen: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub lifesteal_percent: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub spell_lifesteal: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_movement_speed: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub movement_speed_percent_bonus: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_movement_speed_pct: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub base_attack_range: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_attack_range: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_projectile_speed: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub spell_amp: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_spell_amp: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cast_range_bonus: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_cast_range: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bonus_cooldown: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cleave_damage_percent: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cleave_distance: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub chain_strikes: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bash_stun: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub bash_duration: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cold_slow_melee: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cold_slow_ranged: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cold_attack_slow_melee: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cold_attack_slow_ranged: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cold_duration: Option<f32>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub corruption_duration: Option<f32>,
+}
+
+impl ItemSpecials
+{
+    // The keys read as stats, every other special value is something the item's ability does
+    pub fn StatKeys() -> Vec<String>
+    {
+        match serde_json::to_value(ItemSpecials::default())
+        {
+            Ok(Value::Object(fields)) => fields.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// A hero from dotaconstants heroes.json
+#[derive(Debug, Deserialize)]
+pub struct HeroRecord
+{
+    pub localized_name: String,
+    pub primary_attr: String,
+    pub attack_type: String,
+    // The base attack time (BAT), e.g. 1.7
+    #[serde(deserialize_with = "Number")]
+    pub attack_rate: f32,
+    // Despite the name this is the base attack speed, e.g. 100
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_attack_time: Option<f32>,
+    // Older data does not have all of these, they are 0 then. turn_rate is null for some heroes.
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_str: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_agi: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_int: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub str_gain: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub agi_gain: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub int_gain: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_attack_min: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_attack_max: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_armor: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_mr: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub attack_range: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub projectile_speed: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub attack_point: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub move_speed: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub turn_rate: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_health: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_health_regen: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_mana: Option<f32>,
+    #[serde(default, deserialize_with = "NumberOrString")]
+    pub base_mana_regen: Option<f32>,
+}
+
+// An item from dotaconstants items.json
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ItemRecord
+{
+    pub dname: Option<String>,
+    #[serde(deserialize_with = "NumberOrString")]
+    pub cost: Option<f32>,
+    // Names without "item_", the recipe is not listed
+    pub components: Option<Vec<String>>,
+    // Neutral items only, 1-5
+    #[serde(deserialize_with = "NumberOrString")]
+    pub tier: Option<f32>,
+}
+
+pub type ItemRecords = BTreeMap<String, ItemRecord>;
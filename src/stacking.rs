@@ -0,0 +1,210 @@
+// How the stats of several items add up. Summing the columns is wrong for crits, bashes, armor reduction, evasion...
+
+use crate::dps::AverageHitDamage;
+use crate::model::{Item, OnHitEffects};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackingGroup
+{
+    // Adds up
+    Additive,
+    // Only the highest value counts, e.g. two Yashas do not give twice the move speed
+    Unique,
+    // Each item applies to what the others let through: 1 - (1 - a) * (1 - b)
+    Multiplicative,
+    // The groups below take all their stats from the strongest item of the group
+    // Daedalus/Crystalys, the crit with the highest average damage
+    UniqueCrit,
+    // Skull Basher/Abyssal Blade, only one bash rolls
+    UniqueBash,
+    // Monkey King Bar/Javelin
+    UniquePierce,
+    // Maelstrom/Mjollnir/Gleipnir
+    UniqueChainLightning,
+    // Desolator and Orb of Corrosion do not stack
+    ArmorReduction,
+}
+
+// How strong an item is within one of the Unique* groups or ArmorReduction
+fn GroupStrength(Group: StackingGroup, InItem: &Item) -> f32
+{
+    let onHit = &InItem.OnHit;
+    match Group
+    {
+        StackingGroup::UniqueCrit => AverageHitDamage(1.0, InItem.CritChance, InItem.CritMultiplier),
+        StackingGroup::UniqueBash => onHit.BashChance_Melee.max(onHit.BashChance_Ranged) * (1.0 + onHit.BashDamage) * (1.0 + onHit.BashStun),
+        StackingGroup::UniquePierce => onHit.TrueStrikeChance * (1.0 + onHit.PierceDamage),
+        StackingGroup::UniqueChainLightning => onHit.ChainChance * onHit.ChainDamage * onHit.ChainStrikes.max(1) as f32,
+        StackingGroup::ArmorReduction => InItem.ArmorCorruption as f32,
+        StackingGroup::Additive | StackingGroup::Unique | StackingGroup::Multiplicative => 0.0,
+    }
+}
+
+fn StrongestItem(InItems: &[Item], Group: StackingGroup) -> Option<&Item>
+{
+    InItems.iter()
+        .filter(|item| GroupStrength(Group, item) > 0.0)
+        .max_by(|a, b| GroupStrength(Group, a).total_cmp(&GroupStrength(Group, b)))
+}
+
+trait StatValue: Copy
+{
+    fn ToF32(self) -> f32;
+    fn FromF32(InValue: f32) -> Self;
+}
+
+impl StatValue for i32
+{
+    fn ToF32(self) -> f32 { self as f32 }
+    fn FromF32(InValue: f32) -> i32 { InValue.round() as i32 }
+}
+
+impl StatValue for f32
+{
+    fn ToF32(self) -> f32 { self }
+    fn FromF32(InValue: f32) -> f32 { InValue }
+}
+
+// Stacks one stat of InItems by its group
+fn Stack<T: StatValue>(InItems: &[Item], Group: StackingGroup, Get: fn(&Item) -> T) -> T
+{
+    let values = InItems.iter().map(|item| Get(item).ToF32());
+    let stacked = match Group
+    {
+        StackingGroup::Additive => values.sum(),
+        StackingGroup::Unique => values.reduce(f32::max).unwrap_or(0.0),
+        StackingGroup::Multiplicative => 1.0 - values.map(|v| 1.0 - v).product::<f32>(),
+        _ =>
+        {
+            // An empty group gives the value of an item without it, e.g. a crit multiplier of 1
+            return Get(StrongestItem(InItems, Group).unwrap_or(&Item::default()));
+        }
+    };
+    T::FromF32(stacked)
+}
+
+// One item with the stats of the whole build
+pub fn CombineItems(InItems: &[Item]) -> Item
+{
+    // Boots, Yasha, Aether Lens, Dragon Lance and Skadi do not stack with themselves, so move speed, ranges and slows are Unique.
+    // MagicDamage/MagicChance_* are summed like the xlsm does.
+    use StackingGroup::*;
+
+    Item
+    {
+        Name: "Build".to_string(),
+        Damage: Stack(InItems, Additive, |i| i.Damage),
+        Damage_Melee: Stack(InItems, Additive, |i| i.Damage_Melee),
+        Damage_Ranged: Stack(InItems, Additive, |i| i.Damage_Ranged),
+        AttackSpeed: Stack(InItems, Additive, |i| i.AttackSpeed),
+        Str: Stack(InItems, Additive, |i| i.Str),
+        Agi: Stack(InItems, Additive, |i| i.Agi),
+        Int: Stack(InItems, Additive, |i| i.Int),
+        ArmorCorruption: Stack(InItems, ArmorReduction, |i| i.ArmorCorruption),
+        MagicDamage: Stack(InItems, Additive, |i| i.MagicDamage),
+        MagicChance_Melee: Stack(InItems, Additive, |i| i.MagicChance_Melee),
+        MagicChance_Ranged: Stack(InItems, Additive, |i| i.MagicChance_Ranged),
+        CritMultiplier: Stack(InItems, UniqueCrit, |i| i.CritMultiplier),
+        CritChance: Stack(InItems, UniqueCrit, |i| i.CritChance),
+        Armor: Stack(InItems, Additive, |i| i.Armor),
+        Evasion: Stack(InItems, Multiplicative, |i| i.Evasion),
+        MagicResistance: Stack(InItems, Multiplicative, |i| i.MagicResistance),
+        StatusResistance: Stack(InItems, Multiplicative, |i| i.StatusResistance),
+        SlowResistance: Stack(InItems, Multiplicative, |i| i.SlowResistance),
+        Health: Stack(InItems, Additive, |i| i.Health),
+        Mana: Stack(InItems, Additive, |i| i.Mana),
+        HealthRegen: Stack(InItems, Additive, |i| i.HealthRegen),
+        ManaRegen: Stack(InItems, Additive, |i| i.ManaRegen),
+        Lifesteal: Stack(InItems, Additive, |i| i.Lifesteal),
+        SpellLifesteal: Stack(InItems, Additive, |i| i.SpellLifesteal),
+        MoveSpeed: Stack(InItems, Unique, |i| i.MoveSpeed),
+        MoveSpeedPercent: Stack(InItems, Unique, |i| i.MoveSpeedPercent),
+        AttackRange_Melee: Stack(InItems, Unique, |i| i.AttackRange_Melee),
+        AttackRange_Ranged: Stack(InItems, Unique, |i| i.AttackRange_Ranged),
+        ProjectileSpeed: Stack(InItems, Unique, |i| i.ProjectileSpeed),
+        SpellAmp: Stack(InItems, Additive, |i| i.SpellAmp),
+        CastRange: Stack(InItems, Unique, |i| i.CastRange),
+        CooldownReduction: Stack(InItems, Multiplicative, |i| i.CooldownReduction),
+        OnHit: OnHitEffects
+        {
+            CleavePercent: Stack(InItems, Additive, |i| i.OnHit.CleavePercent),
+            CleaveRadius: Stack(InItems, Unique, |i| i.OnHit.CleaveRadius),
+            ChainChance: Stack(InItems, UniqueChainLightning, |i| i.OnHit.ChainChance),
+            ChainDamage: Stack(InItems, UniqueChainLightning, |i| i.OnHit.ChainDamage),
+            ChainStrikes: Stack(InItems, UniqueChainLightning, |i| i.OnHit.ChainStrikes),
+            BashChance_Melee: Stack(InItems, UniqueBash, |i| i.OnHit.BashChance_Melee),
+            BashChance_Ranged: Stack(InItems, UniqueBash, |i| i.OnHit.BashChance_Ranged),
+            BashDamage: Stack(InItems, UniqueBash, |i| i.OnHit.BashDamage),
+            BashStun: Stack(InItems, UniqueBash, |i| i.OnHit.BashStun),
+            TrueStrikeChance: Stack(InItems, UniquePierce, |i| i.OnHit.TrueStrikeChance),
+            PierceDamage: Stack(InItems, UniquePierce, |i| i.OnHit.PierceDamage),
+            SlowMoveSpeed_Melee: Stack(InItems, Unique, |i| i.OnHit.SlowMoveSpeed_Melee),
+            SlowMoveSpeed_Ranged: Stack(InItems, Unique, |i| i.OnHit.SlowMoveSpeed_Ranged),
+            SlowAttackSpeed_Melee: Stack(InItems, Unique, |i| i.OnHit.SlowAttackSpeed_Melee),
+            SlowAttackSpeed_Ranged: Stack(InItems, Unique, |i| i.OnHit.SlowAttackSpeed_Ranged),
+            SlowDuration: Stack(InItems, Unique, |i| i.OnHit.SlowDuration),
+            CorruptionDuration: Stack(InItems, ArmorReduction, |i| i.OnHit.CorruptionDuration),
+        },
+        Cost: Stack(InItems, Additive, |i| i.Cost),
+        IsNeutralItem: InItems.iter().any(|item| item.IsNeutralItem),
+        IsUselessItem: InItems.iter().all(|item| item.IsUselessItem),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn Bash(Chance: f32, Damage: f32, Stun: f32) -> Item
+    {
+        Item{OnHit: OnHitEffects{BashChance_Melee: Chance, BashDamage: Damage, BashStun: Stun, ..Default::default()}, ..Default::default()}
+    }
+
+    #[test]
+    fn StatsAddUp()
+    {
+        let ogreAxe = Item{Str: 10, Cost: 1000, ..Default::default()};
+        let build = CombineItems(&[ogreAxe.clone(), ogreAxe]);
+        assert_eq!((build.Str, build.Cost), (20, 2000));
+        assert_eq!(build.CritMultiplier, 1.0);
+    }
+
+    #[test]
+    fn DaedalusDoesNotStack()
+    {
+        let crystalys = Item{Damage: 15, CritChance: 0.3, CritMultiplier: 1.6, ..Default::default()};
+        let daedalus = Item{Damage: 88, CritChance: 0.3, CritMultiplier: 2.25, ..Default::default()};
+
+        let build = CombineItems(&[daedalus.clone(), crystalys, daedalus]);
+        assert_eq!(build.Damage, 191);
+        assert_eq!((build.CritChance, build.CritMultiplier), (0.3, 2.25));
+    }
+
+    #[test]
+    fn OnlyTheStrongestBashRolls()
+    {
+        // Skull Basher and Abyssal Blade
+        let build = CombineItems(&[Bash(0.25, 100.0, 1.5), Bash(0.25, 100.0, 2.0)]);
+        assert_eq!((build.OnHit.BashChance_Melee, build.OnHit.BashDamage, build.OnHit.BashStun), (0.25, 100.0, 2.0));
+    }
+
+    #[test]
+    fn DesolatorAndOrbOfCorrosionDoNotStack()
+    {
+        let desolator = Item{ArmorCorruption: 6, OnHit: OnHitEffects{CorruptionDuration: 7.0, ..Default::default()}, ..Default::default()};
+        let orb = Item{ArmorCorruption: 3, OnHit: OnHitEffects{CorruptionDuration: 3.0, ..Default::default()}, ..Default::default()};
+
+        let build = CombineItems(&[orb, desolator]);
+        assert_eq!((build.ArmorCorruption, build.OnHit.CorruptionDuration), (6, 7.0));
+    }
+
+    #[test]
+    fn EvasionIsMultiplicative()
+    {
+        // Butterfly and Talisman of Evasion: 1 - 0.65 * 0.85
+        let build = CombineItems(&[Item{Evasion: 0.35, ..Default::default()}, Item{Evasion: 0.15, ..Default::default()}]);
+        assert!((build.Evasion - 0.4475).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,23 @@
+// Helpers shared by the tests of several modules
+
+use crate::model::{Hero, PrimaryAttribute};
+
+pub fn Close(a: f32, b: f32) -> bool
+{
+    (a - b).abs() < 0.001
+}
+
+// A melee agility hero with round numbers, change the fields a test cares about with ..TestHero()
+pub fn TestHero() -> Hero
+{
+    Hero
+    {
+        Name: "Test".to_string(), PrimaryAttribute: PrimaryAttribute::Agility, AttackType: "Melee".to_string(),
+        BAT: 1.7, BaseAttackSpeed: 100,
+        BaseStr: 20.0, BaseAgi: 20.0, BaseInt: 20.0, StrGain: 2.0, AgiGain: 3.0, IntGain: 1.0,
+        BaseAttackMin: 30, BaseAttackMax: 34, BaseArmor: 1.0, BaseMagicResist: 0.25,
+        BaseHealth: 120.0, BaseHealthRegen: 0.5, BaseMana: 75.0, BaseManaRegen: 0.0,
+        AttackPoint: 0.5, ProjectileSpeed: 1000,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,65 @@
+// When a hero's attacks land, from its attack animation and projectile speed
+
+use crate::dps::{AttacksPerSecond, IsRanged, MAX_ATTACK_SPEED, MIN_ATTACK_SPEED};
+use crate::model::Hero;
+
+// Hits the spreadsheet shows the time of
+pub const SHEET_HITS: [i32; 3] = [1, 3, 5];
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AttackTimings
+{
+    // Seconds of the animation before the attack goes off, shrinks with attack speed
+    pub AttackPoint: f32,
+    // Seconds from one attack to the next
+    pub Interval: f32,
+    // Seconds the projectile flies, 0 for melee heroes
+    pub TravelTime: f32,
+}
+
+// InHero's attack timings at AttackSpeed against a target Distance away
+pub fn GetAttackTimings(InHero: &Hero, AttackSpeed: f32, Distance: f32) -> AttackTimings
+{
+    let speedFactor = AttackSpeed.clamp(MIN_ATTACK_SPEED, MAX_ATTACK_SPEED) / 100.0;
+    let travelTime = if IsRanged(InHero) && InHero.ProjectileSpeed > 0 { Distance.max(0.0) / InHero.ProjectileSpeed as f32 } else { 0.0 };
+
+    AttackTimings
+    {
+        AttackPoint: InHero.AttackPoint / speedFactor,
+        Interval: 1.0 / AttacksPerSecond(InHero.BAT, AttackSpeed),
+        TravelTime: travelTime,
+    }
+}
+
+// Seconds from the start of the first attack until hit number Hit lands, 1 is the first hit
+pub fn GetHitTime(InHero: &Hero, AttackSpeed: f32, Distance: f32, Hit: i32) -> f32
+{
+    let timings = GetAttackTimings(InHero, AttackSpeed, Distance);
+    timings.AttackPoint + (Hit.max(1) - 1) as f32 * timings.Interval + timings.TravelTime
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::testutil::{Close, TestHero};
+
+    #[test]
+    fn MeleeHitsDoNotTravel()
+    {
+        let hero = TestHero();
+        assert!(Close(GetHitTime(&hero, 100.0, 600.0, 1), 0.5));
+        // Twice the attack speed halves the animation and the time between attacks
+        assert!(Close(GetHitTime(&hero, 200.0, 0.0, 3), 0.25 + 2.0 * 0.85));
+        assert!(Close(GetAttackTimings(&hero, 200.0, 0.0).Interval, 0.85));
+    }
+
+    #[test]
+    fn RangedHitsWaitForTheProjectile()
+    {
+        let hero = Hero{AttackType: "Ranged".to_string(), ..TestHero()};
+        assert!(Close(GetHitTime(&hero, 100.0, 600.0, 1), 0.5 + 0.6));
+        assert!(Close(GetHitTime(&hero, 100.0, 600.0, 2), 0.5 + 1.7 + 0.6));
+        assert_eq!(GetHitTime(&hero, 100.0, 600.0, 0), GetHitTime(&hero, 100.0, 600.0, 1));
+    }
+}